@@ -0,0 +1,76 @@
+//! Generates the year modules and the solution registry from the files under
+//! `src/yYYYY/`.
+//!
+//! Every `*.rs` file in a year directory is declared as a module, and every
+//! `dayNN.rs` among them is registered under `Key::new(YYYY, NN)`. Such a file
+//! must export a solution named `DayNN` that implements `Default`.
+
+use std::env;
+use std::fmt::Write;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+fn main() {
+    println!("cargo:rerun-if-changed=src");
+
+    let src = Path::new(&env::var("CARGO_MANIFEST_DIR").unwrap()).join("src");
+    let mut years = vec![];
+
+    for entry in fs::read_dir(&src).unwrap() {
+        let path = entry.unwrap().path();
+        if let Some(year) = parse_number(&path, "y") {
+            if path.is_dir() {
+                years.push((year, path));
+            }
+        }
+    }
+    years.sort();
+
+    let mut modules = String::new();
+    let mut registry = String::new();
+
+    for (year, dir) in years.iter() {
+        let mut files: Vec<PathBuf> = fs::read_dir(dir)
+            .unwrap()
+            .map(|e| e.unwrap().path())
+            .filter(|p| p.extension().is_some_and(|e| e == "rs"))
+            .collect();
+        files.sort();
+
+        writeln!(modules, "pub mod y{} {{", year).unwrap();
+        for file in files.iter() {
+            let name = file.file_stem().unwrap().to_str().unwrap();
+            writeln!(modules, "    #[path = {:?}]", file.display().to_string()).unwrap();
+            writeln!(modules, "    pub mod {};", name).unwrap();
+
+            if let Some(day) = parse_number(file, "day") {
+                writeln!(
+                    registry,
+                    "        {}, {} => y{}::{}::Day{:02}::default(),",
+                    year, day, year, name, day
+                )
+                .unwrap();
+            }
+        }
+        writeln!(modules, "}}").unwrap();
+    }
+
+    let out = format!(
+        "{}\n/// Returns every registered solution, keyed by year and day.\n#[allow(clippy::default_constructed_unit_structs)]\npub fn registry() -> Registry {{\n    make_map!(\n{}    )\n}}\n",
+        modules, registry
+    );
+
+    let dest = Path::new(&env::var("OUT_DIR").unwrap()).join("registry.rs");
+    fs::write(dest, out).unwrap();
+}
+
+/// Parses names like `y2021` or `day04.rs` into their number.
+fn parse_number(path: &Path, prefix: &str) -> Option<u32> {
+    let stem = path.file_stem()?.to_str()?;
+    let digits = stem.strip_prefix(prefix)?;
+
+    if digits.is_empty() || !digits.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    digits.parse().ok()
+}
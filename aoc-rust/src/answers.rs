@@ -0,0 +1,100 @@
+use crate::error::Error;
+use crate::solution::{Key, Part};
+use std::collections::HashMap;
+use std::str::FromStr;
+
+pub const DEFAULT_PATH: &str = "answers.txt";
+
+/// Known answers, one per line in the form `y2021/day04 2 1924`.
+///
+/// Blank lines and lines starting with `#` are ignored.
+#[derive(PartialEq, Eq, Debug, Default)]
+pub struct Answers {
+    answers: HashMap<(Key, Part), String>,
+}
+
+impl Answers {
+    pub fn get(&self, key: &Key, part: Part) -> Option<&str> {
+        self.answers.get(&(*key, part)).map(|s| s.as_str())
+    }
+}
+
+impl FromStr for Answers {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut answers = HashMap::new();
+
+        for (n, line) in s.lines().enumerate() {
+            let mut tokens = line
+                .split_whitespace()
+                .map(|t| (t.as_ptr() as usize - line.as_ptr() as usize + 1, t));
+
+            let (column, key) = match tokens.next() {
+                Some((_, t)) if t.starts_with('#') => continue,
+                Some(token) => token,
+                None => continue,
+            };
+            let err = |column: usize, text: &str, msg: String| {
+                Error::parse_at(n + 1, column, msg).with_text(text)
+            };
+
+            let key = key.parse::<Key>().map_err(|e| err(column, key, e))?;
+            let (column, part) = tokens
+                .next()
+                .ok_or_else(|| err(line.len() + 1, "", "missing part".to_string()))?;
+            let part = part.parse::<Part>().map_err(|e| err(column, part, e))?;
+
+            let column = tokens.clone().next().map_or(line.len() + 1, |(c, _)| c);
+            let answer = tokens.map(|(_, t)| t).collect::<Vec<_>>().join(" ");
+            if answer.is_empty() {
+                return Err(err(column, "", "missing answer".to_string()));
+            }
+
+            if answers.insert((key, part), answer).is_some() {
+                let msg = format!("duplicate answer for {} part {}", key, part.number());
+                return Err(err(1, line.trim_end(), msg));
+            }
+        }
+
+        Ok(Answers { answers })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn answers_from_str() {
+        let src = r#"# known answers
+y2021/day04 1 4512
+y2021/day04 2 1924
+
+y2021/day06  1  5934
+"#;
+        let answers = src.parse::<Answers>().unwrap();
+
+        assert_eq!(answers.get(&Key::new(2021, 4), Part::One), Some("4512"));
+        assert_eq!(answers.get(&Key::new(2021, 4), Part::Two), Some("1924"));
+        assert_eq!(answers.get(&Key::new(2021, 6), Part::One), Some("5934"));
+        assert_eq!(answers.get(&Key::new(2021, 6), Part::Two), None);
+    }
+
+    #[test]
+    fn answers_from_str_error() {
+        assert_eq!(
+            "y2021/day04 1".parse::<Answers>(),
+            Err(Error::parse_at(1, 14, "missing answer"))
+        );
+        assert_eq!(
+            "\ny2021/day04  3 1".parse::<Answers>(),
+            Err(Error::parse_at(2, 14, "invalid part '3': must be 1 or 2").with_text("3"))
+        );
+        assert!("y2021/day04 3 1".parse::<Answers>().is_err());
+        assert!("day04 1 1".parse::<Answers>().is_err());
+        assert!("y2021/day04 1 1\ny2021/day04 1 2"
+            .parse::<Answers>()
+            .is_err());
+    }
+}
@@ -0,0 +1,98 @@
+use std::time::{Duration, Instant};
+
+pub const DEFAULT_RUNS: usize = 10;
+
+/// Summary of repeated wall time measurements.
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub struct Stats {
+    pub min: Duration,
+    pub median: Duration,
+    pub max: Duration,
+}
+
+impl Stats {
+    pub fn from_samples(samples: &[Duration]) -> Option<Stats> {
+        let mut samples = samples.to_vec();
+        samples.sort();
+
+        Some(Stats {
+            min: *samples.first()?,
+            median: samples[samples.len() / 2],
+            max: *samples.last()?,
+        })
+    }
+}
+
+/// Calls `f` `runs` times and returns the last result with the elapsed time of
+/// each call.
+pub fn measure<T, F>(runs: usize, mut f: F) -> (Option<T>, Vec<Duration>)
+where
+    F: FnMut() -> T,
+{
+    let mut last = None;
+    let mut samples = Vec::with_capacity(runs);
+
+    for _ in 0..runs {
+        let start = Instant::now();
+        let ret = std::hint::black_box(f());
+        samples.push(start.elapsed());
+        last = Some(ret);
+    }
+
+    (last, samples)
+}
+
+/// Formats a duration with a unit suited to its magnitude, e.g. `12.3ms`.
+pub fn format_duration(d: Duration) -> String {
+    let ns = d.as_nanos() as f64;
+
+    if ns < 1e3 {
+        format!("{}ns", ns)
+    } else if ns < 1e6 {
+        format!("{:.1}µs", ns / 1e3)
+    } else if ns < 1e9 {
+        format!("{:.1}ms", ns / 1e6)
+    } else {
+        format!("{:.2}s", ns / 1e9)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn stats_from_samples() {
+        let ms = Duration::from_millis;
+
+        assert_eq!(Stats::from_samples(&[]), None);
+        assert_eq!(
+            Stats::from_samples(&[ms(5), ms(1), ms(3)]),
+            Some(Stats {
+                min: ms(1),
+                median: ms(3),
+                max: ms(5),
+            })
+        );
+    }
+
+    #[test]
+    fn test_measure() {
+        let mut n = 0;
+        let (last, samples) = measure(3, || {
+            n += 1;
+            n
+        });
+
+        assert_eq!(last, Some(3));
+        assert_eq!(samples.len(), 3);
+    }
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(Duration::from_nanos(999)), "999ns");
+        assert_eq!(format_duration(Duration::from_nanos(12_345)), "12.3µs");
+        assert_eq!(format_duration(Duration::from_micros(4_560)), "4.6ms");
+        assert_eq!(format_duration(Duration::from_millis(1_234)), "1.23s");
+    }
+}
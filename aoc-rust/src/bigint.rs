@@ -0,0 +1,260 @@
+//! An arbitrary-precision unsigned integer for answers that overflow `u128`.
+
+use crate::error::Error;
+use crate::matrix::Scalar;
+use std::cmp::Ordering;
+use std::fmt::{self, Display};
+use std::ops::{Add, AddAssign, Mul, MulAssign};
+use std::str::FromStr;
+
+/// Stored as base-2³² digits, least significant first, with no trailing
+/// zero digits, so zero is empty.
+#[derive(PartialEq, Eq, Debug, Default, Clone, Hash)]
+pub struct BigUint {
+    digits: Vec<u32>,
+}
+
+impl BigUint {
+    pub fn zero() -> BigUint {
+        BigUint::default()
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.digits.is_empty()
+    }
+
+    fn normalize(mut self) -> BigUint {
+        while self.digits.last() == Some(&0) {
+            self.digits.pop();
+        }
+        self
+    }
+
+    /// Divides in place by a small divisor and returns the remainder.
+    fn div_rem_small(&mut self, divisor: u32) -> u32 {
+        let mut rem = 0_u64;
+        for d in self.digits.iter_mut().rev() {
+            let cur = rem << 32 | *d as u64;
+            *d = (cur / divisor as u64) as u32;
+            rem = cur % divisor as u64;
+        }
+        while self.digits.last() == Some(&0) {
+            self.digits.pop();
+        }
+        rem as u32
+    }
+}
+
+impl From<u64> for BigUint {
+    fn from(v: u64) -> Self {
+        BigUint::from(v as u128)
+    }
+}
+
+impl From<u128> for BigUint {
+    fn from(mut v: u128) -> Self {
+        let mut digits = vec![];
+        while v > 0 {
+            digits.push(v as u32);
+            v >>= 32;
+        }
+        BigUint { digits }
+    }
+}
+
+impl Add<&BigUint> for &BigUint {
+    type Output = BigUint;
+
+    fn add(self, rhs: &BigUint) -> BigUint {
+        let (long, short) = if self.digits.len() >= rhs.digits.len() {
+            (&self.digits, &rhs.digits)
+        } else {
+            (&rhs.digits, &self.digits)
+        };
+
+        let mut digits = Vec::with_capacity(long.len() + 1);
+        let mut carry = 0_u64;
+        for (i, &d) in long.iter().enumerate() {
+            let sum = d as u64 + *short.get(i).unwrap_or(&0) as u64 + carry;
+            digits.push(sum as u32);
+            carry = sum >> 32;
+        }
+        if carry > 0 {
+            digits.push(carry as u32);
+        }
+
+        BigUint { digits }
+    }
+}
+
+impl Add for BigUint {
+    type Output = BigUint;
+    fn add(self, rhs: Self) -> Self::Output {
+        &self + &rhs
+    }
+}
+
+impl AddAssign<&BigUint> for BigUint {
+    fn add_assign(&mut self, rhs: &BigUint) {
+        *self = &*self + rhs;
+    }
+}
+
+impl Mul<&BigUint> for &BigUint {
+    type Output = BigUint;
+
+    fn mul(self, rhs: &BigUint) -> BigUint {
+        if self.is_zero() || rhs.is_zero() {
+            return BigUint::zero();
+        }
+
+        let mut digits = vec![0_u32; self.digits.len() + rhs.digits.len()];
+        for (i, &a) in self.digits.iter().enumerate() {
+            let mut carry = 0_u64;
+            for (j, &b) in rhs.digits.iter().enumerate() {
+                let cur = a as u64 * b as u64 + digits[i + j] as u64 + carry;
+                digits[i + j] = cur as u32;
+                carry = cur >> 32;
+            }
+            digits[i + rhs.digits.len()] = carry as u32;
+        }
+
+        BigUint { digits }.normalize()
+    }
+}
+
+impl Mul for BigUint {
+    type Output = BigUint;
+    fn mul(self, rhs: Self) -> Self::Output {
+        &self * &rhs
+    }
+}
+
+impl MulAssign<&BigUint> for BigUint {
+    fn mul_assign(&mut self, rhs: &BigUint) {
+        *self = &*self * rhs;
+    }
+}
+
+impl Ord for BigUint {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.digits
+            .len()
+            .cmp(&other.digits.len())
+            .then_with(|| self.digits.iter().rev().cmp(other.digits.iter().rev()))
+    }
+}
+
+impl PartialOrd for BigUint {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Scalar for BigUint {
+    fn zero() -> Self {
+        BigUint::zero()
+    }
+
+    fn one() -> Self {
+        BigUint::from(1_u64)
+    }
+}
+
+/// Chunks of 9 decimal digits, the most that fit in a `u32`.
+const DECIMAL_CHUNK: u32 = 1_000_000_000;
+
+impl Display for BigUint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut n = self.clone();
+        let mut chunks = vec![];
+        while !n.is_zero() {
+            chunks.push(n.div_rem_small(DECIMAL_CHUNK));
+        }
+
+        let mut s = chunks.last().map_or("0".to_string(), |c| c.to_string());
+        for c in chunks.iter().rev().skip(1) {
+            s.push_str(&format!("{:09}", c));
+        }
+        f.pad_integral(true, "", &s)
+    }
+}
+
+impl FromStr for BigUint {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.is_empty() {
+            return Err(Error::parse("empty number"));
+        }
+
+        let ten = BigUint::from(10_u64);
+        let mut n = BigUint::zero();
+        for (i, c) in s.char_indices() {
+            let d = c.to_digit(10).ok_or_else(|| {
+                Error::parse_at(1, i + 1, format!("invalid digit '{}'", c)).with_text(c)
+            })?;
+            n = &(&n * &ten) + &BigUint::from(d as u64);
+        }
+        Ok(n)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn big(s: &str) -> BigUint {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn display() {
+        assert_eq!(BigUint::zero().to_string(), "0");
+        assert_eq!(BigUint::from(26984457539_u64).to_string(), "26984457539");
+        assert_eq!(BigUint::from(u128::MAX).to_string(), u128::MAX.to_string());
+        assert_eq!(format!("{:>5}", BigUint::from(42_u64)), "   42");
+        assert_eq!(
+            big("1000000000000000000000").to_string(),
+            "1000000000000000000000"
+        );
+    }
+
+    #[test]
+    fn arithmetic() {
+        let max = BigUint::from(u128::MAX);
+
+        assert_eq!(
+            (&max + &BigUint::from(1_u64)).to_string(),
+            "340282366920938463463374607431768211456"
+        );
+        assert_eq!(
+            (&max * &max).to_string(),
+            "115792089237316195423570985008687907852589419931798687112530834793049593217025"
+        );
+        assert_eq!(&max * &BigUint::zero(), BigUint::zero());
+
+        let mut f = BigUint::from(1_u64);
+        for i in 1..=30_u64 {
+            f *= &BigUint::from(i);
+        }
+        assert_eq!(f, big("265252859812191058636308480000000"));
+    }
+
+    #[test]
+    fn ordering() {
+        assert!(big(&format!("1{}", "0".repeat(40))) > BigUint::from(u128::MAX));
+        assert!(big("10000000000000000000000") < BigUint::from(u128::MAX));
+        assert!(big("12") < big("21"));
+        assert_eq!(big("007"), BigUint::from(7_u64));
+    }
+
+    #[test]
+    fn from_str_error() {
+        assert_eq!(
+            "12a".parse::<BigUint>(),
+            Err(Error::parse_at(1, 3, "invalid digit 'a'").with_text("a"))
+        );
+        assert!("".parse::<BigUint>().is_err());
+    }
+}
@@ -0,0 +1,187 @@
+//! Fixed-width binary numbers such as the `0101` rows of a diagnostic report.
+
+use crate::error::Error;
+use std::fmt::{self, Display};
+use std::str::FromStr;
+
+pub const MAX_WIDTH: u32 = u128::BITS;
+
+/// A binary number of `width` bits. Bit 0 is the least significant one, i.e.
+/// the rightmost character of its string form.
+#[derive(PartialEq, Eq, Debug, Default, Clone, Copy, Hash, PartialOrd, Ord)]
+pub struct Bits {
+    value: u128,
+    width: u32,
+}
+
+impl Bits {
+    /// Bits of `value` beyond `width` are dropped.
+    pub fn new(value: u128, width: u32) -> Bits {
+        assert!(width <= MAX_WIDTH, "width {} exceeds {}", width, MAX_WIDTH);
+        Bits {
+            value: value & mask(width),
+            width,
+        }
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn get(&self, i: u32) -> bool {
+        i < self.width && self.value >> i & 1 == 1
+    }
+
+    pub fn count_ones(&self) -> u32 {
+        self.value.count_ones()
+    }
+
+    /// Flips every bit within the width, e.g. `0101` to `1010`.
+    pub fn complement(&self) -> Bits {
+        Bits::new(!self.value, self.width)
+    }
+}
+
+fn mask(width: u32) -> u128 {
+    u128::MAX.checked_shr(MAX_WIDTH - width).unwrap_or(0)
+}
+
+impl From<Bits> for u128 {
+    fn from(b: Bits) -> Self {
+        b.value
+    }
+}
+
+impl TryFrom<Bits> for u64 {
+    type Error = std::num::TryFromIntError;
+
+    fn try_from(b: Bits) -> Result<Self, Self::Error> {
+        u64::try_from(b.value)
+    }
+}
+
+impl Display for Bits {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.width == 0 {
+            return Ok(());
+        }
+        write!(f, "{:0width$b}", self.value, width = self.width as usize)
+    }
+}
+
+impl FromStr for Bits {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.len() > MAX_WIDTH as usize {
+            let msg = format!("binary number is wider than {} bits", MAX_WIDTH);
+            return Err(Error::parse(msg).with_text(s));
+        }
+
+        let mut value = 0;
+        for (i, c) in s.char_indices() {
+            let bit = match c {
+                '0' => 0,
+                '1' => 1,
+                _ => {
+                    let msg = format!("invalid character '{}'", c);
+                    return Err(Error::parse_at(1, i + 1, msg).with_text(c));
+                }
+            };
+            value = value << 1 | bit;
+        }
+
+        Ok(Bits::new(value, s.len() as u32))
+    }
+}
+
+/// The number of ones at each bit position across `items`, indexed like
+/// [`Bits::get`]. The result is as long as the widest item.
+pub fn column_counts(items: &[Bits]) -> Vec<usize> {
+    let width = items.iter().map(|b| b.width).max().unwrap_or(0);
+
+    (0..width)
+        .map(|i| items.iter().filter(|b| b.get(i)).count())
+        .collect()
+}
+
+/// The most common bit at each position across `items`, as wide as the widest
+/// item. Where ones and zeros are equally common, the bit is `tie`.
+pub fn most_common(items: &[Bits], tie: bool) -> Bits {
+    common(items, |ones, zeros| ones > zeros || (tie && ones == zeros))
+}
+
+/// The least common bit at each position across `items`, as wide as the widest
+/// item. Where ones and zeros are equally common, the bit is `tie`.
+pub fn least_common(items: &[Bits], tie: bool) -> Bits {
+    common(items, |ones, zeros| ones < zeros || (tie && ones == zeros))
+}
+
+/// Sets each bit for which `pick(ones, zeros)` holds.
+fn common<F: Fn(usize, usize) -> bool>(items: &[Bits], pick: F) -> Bits {
+    let counts = column_counts(items);
+    let value = counts
+        .iter()
+        .enumerate()
+        .filter(|(_, &ones)| pick(ones, items.len() - ones))
+        .fold(0, |acc, (i, _)| acc | 1 << i);
+
+    Bits::new(value, counts.len() as u32)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn bits_from_str() {
+        let b = "01011".parse::<Bits>().unwrap();
+
+        assert_eq!(b, Bits::new(11, 5));
+        assert_eq!(b.to_string(), "01011");
+        assert!(b.get(0) && b.get(1) && !b.get(2) && b.get(3) && !b.get(4));
+        assert!(!b.get(5));
+        assert_eq!(b.count_ones(), 3);
+        assert_eq!(
+            "012".parse::<Bits>(),
+            Err(Error::parse_at(1, 3, "invalid character '2'").with_text("2"))
+        );
+        assert!("1".repeat(129).parse::<Bits>().is_err());
+        assert_eq!("".parse::<Bits>(), Ok(Bits::default()));
+    }
+
+    #[test]
+    fn conversions() {
+        let b = "1".repeat(128).parse::<Bits>().unwrap();
+
+        assert_eq!(u128::from(b), u128::MAX);
+        assert!(u64::try_from(b).is_err());
+        assert_eq!(u64::try_from(Bits::new(22, 5)), Ok(22));
+        assert_eq!(b.complement(), Bits::new(0, 128));
+    }
+
+    #[test]
+    fn complement() {
+        assert_eq!(Bits::new(0b0101, 4).complement(), Bits::new(0b1010, 4));
+        assert_eq!(Bits::new(0b0101, 4).complement().to_string(), "1010");
+    }
+
+    #[test]
+    fn common_bits() {
+        let items: Vec<Bits> = ["00100", "11110", "10110", "10111"]
+            .iter()
+            .map(|s| s.parse().unwrap())
+            .collect();
+
+        assert_eq!(column_counts(&items), vec![1, 3, 4, 1, 3]);
+        assert_eq!(most_common(&items, true).to_string(), "10110");
+        assert_eq!(least_common(&items, false).to_string(), "01001");
+
+        let tied: Vec<Bits> = ["110", "101"].iter().map(|s| s.parse().unwrap()).collect();
+        assert_eq!(most_common(&tied, true).to_string(), "111");
+        assert_eq!(most_common(&tied, false).to_string(), "100");
+        assert_eq!(least_common(&tied, true).to_string(), "011");
+        assert_eq!(least_common(&tied, false).to_string(), "000");
+        assert_eq!(most_common(&[], true), Bits::default());
+    }
+}
@@ -0,0 +1,601 @@
+use aoc_rust::answers;
+use aoc_rust::bench;
+use aoc_rust::input;
+use aoc_rust::{Key, Part};
+use std::fmt::Display;
+use std::ops::RangeInclusive;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+pub const USAGE: &str = r#"Usage: aoc-rust <COMMAND> [OPTIONS]
+
+Commands:
+  run [NAME]       Run the selected solutions
+  all              Run the selected solutions (default: all) and print a summary table
+  verify [NAME]    Check the selected solutions (default: all) against known answers
+  bench [NAME]     Time parsing and solving of the selected solutions (default: all)
+  new <YEAR> <DAY> Create src/y<YEAR>/day<DAY>.rs and an empty input from a template
+  list             List the registered solutions
+  help             Print this message
+
+Options for run, all, verify and bench:
+  --year <YEAR>    Year to run, e.g. 2021 or 2020..2021
+  --day <DAY>      Day to run, e.g. 4 or 1..7
+  --part <PART>    Part to run, 1 or 2 (both when omitted)
+  --all            Run every registered solution
+  --input <PATH>   Read the input from PATH, or from stdin if PATH is -
+                   (default: inputs/<year>/<day>.txt)
+  --opt <KEY=VAL>  Set an option of the selected day, e.g. cost=quadratic
+                   for y2021/day07; may be repeated
+
+Options for run and all:
+  --format <FMT>   Output format, text or json (default: text)
+
+Options for verify:
+  --answers <PATH> Read known answers from PATH (default: answers.txt)
+
+Options for bench:
+  --runs <N>       Number of times to run each step (default: 10)
+
+Ranges are inclusive. NAME has the form y2021/day04, or y2021/day04_2 for part 2."#;
+
+const COMMANDS: [&str; 7] = ["run", "all", "verify", "bench", "new", "list", "help"];
+
+#[derive(PartialEq, Eq, Debug)]
+pub enum Command {
+    Run(Selection, Format),
+    All(Selection, Format),
+    Verify(Selection, PathBuf),
+    Bench(Selection, usize),
+    New(Key),
+    List,
+    Help,
+}
+
+impl Command {
+    /// The solutions a command runs on, if it runs any.
+    pub fn selection(&self) -> Option<&Selection> {
+        match self {
+            Command::Run(sel, _)
+            | Command::All(sel, _)
+            | Command::Verify(sel, _)
+            | Command::Bench(sel, _) => Some(sel),
+            Command::New(_) | Command::List | Command::Help => None,
+        }
+    }
+
+    pub fn parse<I>(args: I) -> Result<Command, String>
+    where
+        I: IntoIterator<Item = String>,
+    {
+        let mut args = args.into_iter();
+
+        match args.next().as_deref() {
+            None | Some("help") | Some("-h") | Some("--help") => Ok(Command::Help),
+            Some("list") => Ok(Command::List),
+            Some("run") => {
+                let mut args: Vec<_> = args.collect();
+                let format = Format::take(&mut args)?;
+                Ok(Command::Run(Selection::parse(args.into_iter())?, format))
+            }
+            Some("all") => {
+                let mut args: Vec<_> = args.collect();
+                let format = Format::take(&mut args)?;
+                Ok(Command::All(
+                    Selection::parse_or_all(args.into_iter())?,
+                    format,
+                ))
+            }
+            Some("verify") => {
+                let mut args: Vec<_> = args.collect();
+                let path = take_option(&mut args, "--answers")?
+                    .unwrap_or_else(|| answers::DEFAULT_PATH.to_string());
+                let sel = Selection::parse_or_all(args.into_iter())?;
+                Ok(Command::Verify(sel, PathBuf::from(path)))
+            }
+            Some("bench") => {
+                let mut args: Vec<_> = args.collect();
+                let runs = match take_option(&mut args, "--runs")? {
+                    Some(n) => match n.parse::<usize>() {
+                        Ok(n) if n > 0 => n,
+                        _ => return Err(format!("invalid number of runs '{}'", n)),
+                    },
+                    None => bench::DEFAULT_RUNS,
+                };
+                let sel = Selection::parse_or_all(args.into_iter())?;
+                Ok(Command::Bench(sel, runs))
+            }
+            Some("new") => {
+                let args: Vec<_> = args.collect();
+                let (year, day) = match args.as_slice() {
+                    [year, day] => (year, day),
+                    _ => return Err("new takes exactly two arguments: <YEAR> <DAY>".to_string()),
+                };
+                let year = year
+                    .parse::<u16>()
+                    .map_err(|e| format!("invalid year '{}': {}", year, e))?;
+                let day = match day.parse::<u8>() {
+                    Ok(d) if (1..=25).contains(&d) => d,
+                    _ => return Err(format!("invalid day '{}': must be 1 to 25", day)),
+                };
+                Ok(Command::New(Key::new(year, day)))
+            }
+            Some(other) => {
+                let mut msg = format!("unknown command '{}'", other);
+                if let Some(s) = closest(other, COMMANDS.iter()) {
+                    msg.push_str(&format!(", did you mean '{}'?", s));
+                }
+                Err(msg)
+            }
+        }
+    }
+}
+
+/// Output format of runner results.
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Default)]
+pub enum Format {
+    #[default]
+    Text,
+    /// One JSON object per line.
+    Json,
+}
+
+impl Format {
+    fn take(args: &mut Vec<String>) -> Result<Format, String> {
+        match take_option(args, "--format")? {
+            Some(s) => s.parse(),
+            None => Ok(Format::default()),
+        }
+    }
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(Format::Text),
+            "json" => Ok(Format::Json),
+            _ => Err(format!("invalid format '{}': must be text or json", s)),
+        }
+    }
+}
+
+/// The set of solutions chosen on the command line.
+#[derive(PartialEq, Eq, Debug, Default)]
+pub struct Selection {
+    pub years: Option<RangeInclusive<u16>>,
+    pub days: Option<RangeInclusive<u8>>,
+    pub part: Option<Part>,
+    pub all: bool,
+    pub name: Option<String>,
+    pub input: Option<PathBuf>,
+    pub options: Vec<(String, String)>,
+}
+
+impl Selection {
+    fn is_selected(&self) -> bool {
+        self.all || self.name.is_some() || self.years.is_some() || self.days.is_some()
+    }
+
+    /// Like `parse`, but selects every solution when nothing is selected.
+    fn parse_or_all<I>(args: I) -> Result<Selection, String>
+    where
+        I: Iterator<Item = String>,
+    {
+        let mut sel = Selection::parse(args)?;
+        if !sel.is_selected() {
+            sel.all = true;
+        }
+        Ok(sel)
+    }
+
+    fn parse<I>(mut args: I) -> Result<Selection, String>
+    where
+        I: Iterator<Item = String>,
+    {
+        let mut sel = Selection::default();
+
+        while let Some(arg) = args.next() {
+            let (flag, inline) = match arg.split_once('=') {
+                Some((flag, value)) if arg.starts_with("--") => {
+                    (flag.to_string(), Some(value.to_string()))
+                }
+                _ => (arg.clone(), None),
+            };
+            let mut value = || {
+                inline
+                    .clone()
+                    .or_else(|| args.next())
+                    .ok_or_else(|| format!("missing value for '{}'", flag))
+            };
+
+            match flag.as_str() {
+                "--year" => sel.years = Some(parse_range(&value()?)?),
+                "--day" => sel.days = Some(parse_range(&value()?)?),
+                "--part" => sel.part = Some(value()?.parse()?),
+                "--all" => sel.all = true,
+                "--input" => sel.input = Some(PathBuf::from(value()?)),
+                "--opt" => {
+                    let opt = value()?;
+                    let (k, v) = opt
+                        .split_once('=')
+                        .ok_or_else(|| format!("invalid option '{}': expected KEY=VALUE", opt))?;
+                    sel.options.push((k.to_string(), v.to_string()));
+                }
+                s if s.starts_with('-') => return Err(format!("unknown option '{}'", s)),
+                _ if sel.name.is_none() => sel.name = Some(arg),
+                _ => return Err(format!("unexpected argument '{}'", arg)),
+            }
+        }
+
+        Ok(sel)
+    }
+
+    /// The input for `key`, either given by `--input` or the default location.
+    pub fn input_path(&self, key: &Key) -> PathBuf {
+        self.input
+            .clone()
+            .unwrap_or_else(|| input::default_path(key))
+    }
+
+    /// Resolves the selection against the registered keys, in sorted order.
+    pub fn resolve(&self, keys: &[Key]) -> Result<Vec<(Key, Part)>, String> {
+        let mut keys = keys.to_vec();
+        keys.sort();
+
+        let mut part = self.part;
+        let mut target = None;
+
+        let matched: Vec<Key> = if self.all {
+            keys.clone()
+        } else if let Some(name) = &self.name {
+            let (key, p) = match name.strip_suffix("_2") {
+                Some(name) => (name, Some(Part::Two)),
+                None => (name.as_str(), None),
+            };
+            let key = key
+                .parse::<Key>()
+                .map_err(|e| with_suggestion(e, name, &keys))?;
+            part = part.or(p);
+            target = Some(key);
+            keys.iter().cloned().filter(|k| *k == key).collect()
+        } else if self.years.is_some() || self.days.is_some() {
+            let year = self.years.as_ref().map(|r| *r.start());
+            let day = self.days.as_ref().map(|r| *r.start());
+            target = Some(Key::new(
+                year.unwrap_or_else(|| keys.last().map_or(0, |k| k.year)),
+                day.unwrap_or(1),
+            ));
+            keys.iter()
+                .cloned()
+                .filter(|k| self.years.as_ref().is_none_or(|r| r.contains(&k.year)))
+                .filter(|k| self.days.as_ref().is_none_or(|r| r.contains(&k.day)))
+                .collect()
+        } else {
+            return Err("nothing to run: pass --year/--day, a NAME or --all".to_string());
+        };
+
+        if matched.is_empty() {
+            let msg = "no registered solution matches the selection".to_string();
+            return Err(match target {
+                Some(target) => with_suggestion(msg, &target.to_string(), &keys),
+                None => msg,
+            });
+        }
+
+        if self.input.is_some() && matched.len() > 1 {
+            return Err("--input can only be used when running a single day".to_string());
+        }
+        if !self.options.is_empty() && matched.len() > 1 {
+            return Err("--opt can only be used when running a single day".to_string());
+        }
+
+        let parts = match part {
+            Some(p) => vec![p],
+            None => Part::ALL.to_vec(),
+        };
+
+        Ok(matched
+            .into_iter()
+            .flat_map(|k| parts.iter().map(move |p| (k, *p)))
+            .collect())
+    }
+}
+
+/// Removes `name` and its value from `args`, accepting both `name VALUE` and
+/// `name=VALUE`.
+fn take_option(args: &mut Vec<String>, name: &str) -> Result<Option<String>, String> {
+    let prefix = format!("{}=", name);
+
+    for i in 0..args.len() {
+        if args[i] == name {
+            if i + 1 >= args.len() {
+                return Err(format!("missing value for '{}'", name));
+            }
+            let value = args.remove(i + 1);
+            args.remove(i);
+            return Ok(Some(value));
+        }
+
+        if let Some(value) = args[i].strip_prefix(&prefix) {
+            let value = value.to_string();
+            args.remove(i);
+            return Ok(Some(value));
+        }
+    }
+
+    Ok(None)
+}
+
+/// Groups consecutive targets of the same day, so that each input is parsed once.
+pub fn group_by_day(targets: &[(Key, Part)]) -> Vec<(Key, Vec<Part>)> {
+    let mut groups: Vec<(Key, Vec<Part>)> = vec![];
+
+    for (key, part) in targets.iter() {
+        match groups.last_mut() {
+            Some((k, parts)) if k == key => parts.push(*part),
+            _ => groups.push((*key, vec![*part])),
+        }
+    }
+
+    groups
+}
+
+fn parse_range<T>(s: &str) -> Result<RangeInclusive<T>, String>
+where
+    T: FromStr + PartialOrd + Copy,
+    T::Err: Display,
+{
+    let num = |s: &str| {
+        s.trim()
+            .parse::<T>()
+            .map_err(|e| format!("invalid number '{}': {}", s, e))
+    };
+
+    let (start, end) = match s.split_once("..") {
+        Some((start, end)) => (num(start)?, num(end)?),
+        None => {
+            let n = num(s)?;
+            (n, n)
+        }
+    };
+
+    if start > end {
+        return Err(format!("invalid range '{}': start is after end", s));
+    }
+
+    Ok(start..=end)
+}
+
+fn with_suggestion(msg: String, name: &str, keys: &[Key]) -> String {
+    let names: Vec<_> = keys.iter().map(|k| k.to_string()).collect();
+    match closest(name, names.iter()) {
+        Some(s) => format!("{}, did you mean '{}'?", msg, s),
+        None => msg,
+    }
+}
+
+/// Returns the candidate closest to `target` by edit distance, if any is
+/// reasonably close.
+fn closest<'a, I, T>(target: &str, candidates: I) -> Option<&'a str>
+where
+    I: Iterator<Item = &'a T>,
+    T: AsRef<str> + ?Sized + 'a,
+{
+    let limit = std::cmp::max(target.len() / 2, 2);
+
+    candidates
+        .map(|c| (edit_distance(target, c.as_ref()), c.as_ref()))
+        .filter(|(d, _)| *d <= limit)
+        .min_by_key(|(d, _)| *d)
+        .map(|(_, c)| c)
+}
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    let mut cur = vec![0; b.len() + 1];
+
+    for (i, ca) in a.chars().enumerate() {
+        cur[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let cost = if ca == *cb { 0 } else { 1 };
+            cur[j + 1] = (prev[j] + cost).min(prev[j + 1] + 1).min(cur[j] + 1);
+        }
+        std::mem::swap(&mut prev, &mut cur);
+    }
+
+    prev[b.len()]
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn args(s: &str) -> Vec<String> {
+        s.split_whitespace().map(|s| s.to_string()).collect()
+    }
+
+    fn keys() -> Vec<Key> {
+        (1..=7).map(|d| Key::new(2021, d)).collect()
+    }
+
+    #[test]
+    fn parse_command() {
+        assert_eq!(Command::parse(args("")), Ok(Command::Help));
+        assert_eq!(Command::parse(args("list")), Ok(Command::List));
+        assert_eq!(
+            Command::parse(args("all")),
+            Ok(Command::All(
+                Selection {
+                    all: true,
+                    ..Default::default()
+                },
+                Format::Text
+            ))
+        );
+        assert_eq!(
+            Command::parse(args("all --format json --year 2021")),
+            Ok(Command::All(
+                Selection {
+                    years: Some(2021..=2021),
+                    ..Default::default()
+                },
+                Format::Json
+            ))
+        );
+        assert!(Command::parse(args("run --format xml")).is_err());
+        assert_eq!(
+            Command::parse(args("run --year 2021 --day 1..7 --part=2")),
+            Ok(Command::Run(
+                Selection {
+                    years: Some(2021..=2021),
+                    days: Some(1..=7),
+                    part: Some(Part::Two),
+                    ..Default::default()
+                },
+                Format::Text
+            ))
+        );
+        assert_eq!(
+            Command::parse(args("verify")),
+            Ok(Command::Verify(
+                Selection {
+                    all: true,
+                    ..Default::default()
+                },
+                PathBuf::from("answers.txt")
+            ))
+        );
+        assert_eq!(
+            Command::parse(args("verify --day 4 --answers=a.txt")),
+            Ok(Command::Verify(
+                Selection {
+                    days: Some(4..=4),
+                    ..Default::default()
+                },
+                PathBuf::from("a.txt")
+            ))
+        );
+        assert!(Command::parse(args("verify --answers")).is_err());
+        assert_eq!(
+            Command::parse(args("bench --runs 3 y2021/day06")),
+            Ok(Command::Bench(
+                Selection {
+                    name: Some("y2021/day06".to_string()),
+                    ..Default::default()
+                },
+                3
+            ))
+        );
+        assert!(Command::parse(args("bench --runs 0")).is_err());
+        assert_eq!(
+            Command::parse(args("new 2021 8")),
+            Ok(Command::New(Key::new(2021, 8)))
+        );
+        assert!(Command::parse(args("new 2021")).is_err());
+        assert!(Command::parse(args("new 2021 26")).is_err());
+        assert_eq!(
+            Command::parse(args("rnu")),
+            Err("unknown command 'rnu', did you mean 'run'?".to_string())
+        );
+        assert!(Command::parse(args("run --day")).is_err());
+        assert!(Command::parse(args("run --day 7..1")).is_err());
+        assert!(Command::parse(args("run --part 3")).is_err());
+        assert!(Command::parse(args("run --bogus")).is_err());
+        assert_eq!(
+            Command::parse(args("run y2021/day04 --input day04.txt")),
+            Ok(Command::Run(
+                Selection {
+                    name: Some("y2021/day04".to_string()),
+                    input: Some(PathBuf::from("day04.txt")),
+                    ..Default::default()
+                },
+                Format::Text
+            ))
+        );
+        assert_eq!(
+            Command::parse(args("bench y2021/day07 --opt cost=capped:5 --opt=x=1")),
+            Ok(Command::Bench(
+                Selection {
+                    name: Some("y2021/day07".to_string()),
+                    options: vec![
+                        ("cost".to_string(), "capped:5".to_string()),
+                        ("x".to_string(), "1".to_string())
+                    ],
+                    ..Default::default()
+                },
+                10
+            ))
+        );
+        assert!(Command::parse(args("run --opt cost")).is_err());
+    }
+
+    #[test]
+    fn resolve() {
+        let run = |s: &str| match Command::parse(args(s)).unwrap() {
+            Command::Run(sel, _) => sel.resolve(&keys()),
+            _ => unreachable!(),
+        };
+
+        assert_eq!(
+            run("run --year 2021 --day 4 --part 2"),
+            Ok(vec![(Key::new(2021, 4), Part::Two)])
+        );
+        assert_eq!(
+            run("run --day 2..3 --part 1"),
+            Ok(vec![
+                (Key::new(2021, 2), Part::One),
+                (Key::new(2021, 3), Part::One)
+            ])
+        );
+        assert_eq!(
+            run("run y2021/day04_2"),
+            Ok(vec![(Key::new(2021, 4), Part::Two)])
+        );
+        assert_eq!(run("run --all").unwrap().len(), 14);
+
+        assert_eq!(
+            run("run --year 2021 --day 9"),
+            Err(
+                "no registered solution matches the selection, did you mean 'y2021/day01'?"
+                    .to_string()
+            )
+        );
+        assert_eq!(
+            run("run y2022/day04"),
+            Err(
+                "no registered solution matches the selection, did you mean 'y2021/day04'?"
+                    .to_string()
+            )
+        );
+        assert_eq!(
+            run("run y2021-day04"),
+            Err("invalid name 'y2021-day04': expected the form y2021/day04, did you mean 'y2021/day04'?"
+                .to_string())
+        );
+        assert!(run("run").is_err());
+        assert!(run("run --day 1..2 --input a.txt").is_err());
+        assert!(run("run --day 1..2 --opt a=1").is_err());
+        assert!(run("run --day 2 --opt a=1").is_ok());
+    }
+
+    #[test]
+    fn test_group_by_day() {
+        let (k1, k2) = (Key::new(2021, 1), Key::new(2021, 2));
+
+        assert_eq!(
+            group_by_day(&[(k1, Part::One), (k1, Part::Two), (k2, Part::Two)]),
+            vec![(k1, vec![Part::One, Part::Two]), (k2, vec![Part::Two])]
+        );
+    }
+
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(edit_distance("", ""), 0);
+        assert_eq!(edit_distance("abc", ""), 3);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+        assert_eq!(edit_distance("y2021/day09", "y2021/day07"), 1);
+    }
+}
@@ -0,0 +1,223 @@
+use std::fmt;
+
+/// The error type shared by the solutions and the runner.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub enum Error {
+    /// Reading the input failed.
+    Io(std::io::ErrorKind, String),
+    /// The input is malformed. `line` and `column` are 1-based, `column`
+    /// counting bytes, and `text` is the offending token if known.
+    Parse {
+        line: usize,
+        column: usize,
+        text: String,
+        message: String,
+    },
+    /// The puzzle reached a state its rules do not allow.
+    InvalidState(String),
+    /// The puzzle has no answer for the given input.
+    NoSolution(String),
+    /// The command line is invalid.
+    Usage(String),
+}
+
+impl Error {
+    /// A parse error at the start of the parsed string.
+    pub fn parse<S: Into<String>>(message: S) -> Error {
+        Error::parse_at(1, 1, message)
+    }
+
+    pub fn parse_at<S: Into<String>>(line: usize, column: usize, message: S) -> Error {
+        Error::Parse {
+            line,
+            column,
+            text: String::new(),
+            message: message.into(),
+        }
+    }
+
+    /// Attaches the offending token to a parse error. Other errors are
+    /// returned unchanged.
+    pub fn with_text<S: Into<String>>(self, text: S) -> Error {
+        match self {
+            Error::Parse {
+                line,
+                column,
+                message,
+                ..
+            } => Error::Parse {
+                line,
+                column,
+                text: text.into(),
+                message,
+            },
+            e => e,
+        }
+    }
+
+    /// Relocates a parse error found in a substring to the enclosing string,
+    /// where the substring starts after `lines` lines and, on its first line,
+    /// after `columns` columns. Other errors are returned unchanged.
+    pub fn offset(self, lines: usize, columns: usize) -> Error {
+        match self {
+            Error::Parse {
+                line,
+                column,
+                text,
+                message,
+            } => Error::Parse {
+                line: line + lines,
+                column: if line == 1 { column + columns } else { column },
+                text,
+                message,
+            },
+            e => e,
+        }
+    }
+
+    /// The process exit code for this error.
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            Error::Usage(_) => 2,
+            Error::Io(_, _) => 3,
+            Error::Parse { .. } => 4,
+            Error::InvalidState(_) => 5,
+            Error::NoSolution(_) => 6,
+        }
+    }
+
+    /// Renders the line of `src` a parse error points at, with a caret under
+    /// the offending token, e.g.
+    ///
+    /// ```text
+    ///   12 | 20 11 1O 24  4
+    ///      |       ^^
+    /// ```
+    ///
+    /// Returns `None` for other errors or if the line is not in `src`.
+    pub fn snippet(&self, src: &str) -> Option<String> {
+        let (line, column, text) = match self {
+            Error::Parse {
+                line, column, text, ..
+            } => (*line, *column, text),
+            _ => return None,
+        };
+        let content = src.lines().nth(line.checked_sub(1)?)?;
+
+        // Round down to a char boundary in case the column splits a char.
+        let offset = column.saturating_sub(1).min(content.len());
+        let offset = (0..=offset)
+            .rev()
+            .find(|&i| content.is_char_boundary(i))
+            .unwrap_or(0);
+
+        let indent: String = content[..offset]
+            .chars()
+            .map(|c| if c == '\t' { '\t' } else { ' ' })
+            .collect();
+        let remaining = content[offset..].chars().count();
+        let width = text.chars().count().min(remaining).max(1);
+        let gutter = " ".repeat(line.to_string().len());
+
+        Some(format!(
+            "  {} | {}\n  {} | {}{}",
+            line,
+            content,
+            gutter,
+            indent,
+            "^".repeat(width)
+        ))
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Io(_, message) => write!(f, "{}", message),
+            Error::Parse {
+                line,
+                column,
+                message,
+                ..
+            } => write!(
+                f,
+                "parse error at line {}, column {}: {}",
+                line, column, message
+            ),
+            Error::InvalidState(message) => write!(f, "invalid puzzle state: {}", message),
+            Error::NoSolution(message) => write!(f, "no solution: {}", message),
+            Error::Usage(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<std::io::Error> for Error {
+    fn from(e: std::io::Error) -> Self {
+        Error::Io(e.kind(), e.to_string())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn offset() {
+        assert_eq!(
+            Error::parse_at(1, 3, "x").offset(4, 10),
+            Error::parse_at(5, 13, "x")
+        );
+        assert_eq!(
+            Error::parse_at(2, 3, "x").offset(4, 10),
+            Error::parse_at(6, 3, "x")
+        );
+        assert_eq!(
+            Error::NoSolution("x".to_string()).offset(4, 10),
+            Error::NoSolution("x".to_string())
+        );
+    }
+
+    #[test]
+    fn snippet() {
+        let src = "7,4\n\n22 13 1O 11  0\n";
+        let e = Error::parse_at(3, 7, "invalid number '1O'").with_text("1O");
+
+        assert_eq!(
+            e.snippet(src).unwrap(),
+            "  3 | 22 13 1O 11  0\n    |       ^^"
+        );
+        assert_eq!(
+            Error::parse_at(1, 3, "missing amount")
+                .snippet("up")
+                .unwrap(),
+            "  1 | up\n    |   ^"
+        );
+        assert_eq!(
+            Error::parse_at(1, 0, "x").snippet("up").unwrap(),
+            "  1 | up\n    | ^"
+        );
+        assert_eq!(
+            Error::parse_at(1, 7, "invalid digit 'x'")
+                .with_text("x")
+                .snippet("é,ü,x")
+                .unwrap(),
+            "  1 | é,ü,x\n    |     ^"
+        );
+        assert_eq!(Error::parse_at(9, 1, "x").snippet(src), None);
+        assert_eq!(Error::NoSolution("x".to_string()).snippet(src), None);
+    }
+
+    #[test]
+    fn display() {
+        assert_eq!(
+            Error::parse_at(2, 5, "invalid digit").to_string(),
+            "parse error at line 2, column 5: invalid digit"
+        );
+        assert_eq!(
+            Error::NoSolution("no board wins".to_string()).to_string(),
+            "no solution: no board wins"
+        );
+    }
+}
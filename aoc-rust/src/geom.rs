@@ -0,0 +1,361 @@
+//! 2D points, vectors and bounding boxes on an integer plane.
+//!
+//! The y axis points down, as it does when a puzzle input is read line by line,
+//! so "up" is `y - 1` and turning right turns clockwise on screen.
+
+use crate::error::Error;
+use std::fmt::{self, Display};
+use std::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
+use std::str::FromStr;
+
+/// An integer type usable as a coordinate.
+pub trait Coord: Copy + Ord + Add<Output = Self> + Sub<Output = Self> {
+    const ZERO: Self;
+    const ONE: Self;
+
+    fn checked_add(self, rhs: Self) -> Option<Self>;
+    fn checked_sub(self, rhs: Self) -> Option<Self>;
+
+    /// The absolute difference, which never overflows for unsigned types.
+    fn diff(self, other: Self) -> Self {
+        if self > other {
+            self - other
+        } else {
+            other - self
+        }
+    }
+}
+
+macro_rules! impl_coord {
+    ($($t:ty),+) => {
+        $(impl Coord for $t {
+            const ZERO: Self = 0;
+            const ONE: Self = 1;
+
+            fn checked_add(self, rhs: Self) -> Option<Self> {
+                <$t>::checked_add(self, rhs)
+            }
+
+            fn checked_sub(self, rhs: Self) -> Option<Self> {
+                <$t>::checked_sub(self, rhs)
+            }
+        })+
+    };
+}
+
+impl_coord!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+
+const DIRECTIONS4: [(i8, i8); 4] = [(0, -1), (1, 0), (0, 1), (-1, 0)];
+const DIRECTIONS8: [(i8, i8); 8] = [
+    (0, -1),
+    (1, -1),
+    (1, 0),
+    (1, 1),
+    (0, 1),
+    (-1, 1),
+    (-1, 0),
+    (-1, -1),
+];
+
+#[derive(PartialEq, Eq, Default, Clone, Copy, Debug, Hash, PartialOrd, Ord)]
+pub struct Point<T = i64> {
+    pub x: T,
+    pub y: T,
+}
+
+/// A displacement between two points.
+pub type Vector<T = i64> = Point<T>;
+
+impl<T> Point<T> {
+    pub const fn new(x: T, y: T) -> Point<T> {
+        Point { x, y }
+    }
+}
+
+impl<T: Coord> Point<T> {
+    pub fn manhattan(&self, other: &Point<T>) -> T {
+        self.x.diff(other.x) + self.y.diff(other.y)
+    }
+
+    pub fn chebyshev(&self, other: &Point<T>) -> T {
+        self.x.diff(other.x).max(self.y.diff(other.y))
+    }
+
+    /// The up to 4 orthogonally adjacent points, clockwise from up. Points
+    /// that would overflow `T` are skipped.
+    pub fn neighbors4(&self) -> impl Iterator<Item = Point<T>> {
+        let p = *self;
+        DIRECTIONS4.into_iter().filter_map(move |d| p.step(d))
+    }
+
+    /// The up to 8 orthogonally or diagonally adjacent points, clockwise from
+    /// up. Points that would overflow `T` are skipped.
+    pub fn neighbors8(&self) -> impl Iterator<Item = Point<T>> {
+        let p = *self;
+        DIRECTIONS8.into_iter().filter_map(move |d| p.step(d))
+    }
+
+    fn step(&self, (dx, dy): (i8, i8)) -> Option<Point<T>> {
+        let shift = |v: T, d: i8| match d {
+            -1 => v.checked_sub(T::ONE),
+            1 => v.checked_add(T::ONE),
+            _ => Some(v),
+        };
+
+        Some(Point::new(shift(self.x, dx)?, shift(self.y, dy)?))
+    }
+}
+
+impl<T: Coord + Neg<Output = T>> Point<T> {
+    /// The vector with the sign of each component, e.g. `(3, -2)` to `(1, -1)`.
+    pub fn unit(&self) -> Vector<T> {
+        let signum = |v: T| match v.cmp(&T::ZERO) {
+            std::cmp::Ordering::Less => -T::ONE,
+            std::cmp::Ordering::Equal => T::ZERO,
+            std::cmp::Ordering::Greater => T::ONE,
+        };
+
+        Point::new(signum(self.x), signum(self.y))
+    }
+
+    /// Rotates by 90° clockwise around the origin, e.g. right to down.
+    pub fn rotate_right(&self) -> Point<T> {
+        Point::new(-self.y, self.x)
+    }
+
+    /// Rotates by 90° counterclockwise around the origin, e.g. right to up.
+    pub fn rotate_left(&self) -> Point<T> {
+        Point::new(self.y, -self.x)
+    }
+}
+
+impl<T: Copy + Add<Output = T> + Sub<Output = T> + Mul<Output = T>> Point<T> {
+    pub fn dot(&self, other: &Point<T>) -> T {
+        self.x * other.x + self.y * other.y
+    }
+
+    /// The z component of the 3D cross product, zero when both are parallel.
+    pub fn cross(&self, other: &Point<T>) -> T {
+        self.x * other.y - self.y * other.x
+    }
+}
+
+impl<T: Add<Output = T>> Add for Point<T> {
+    type Output = Point<T>;
+    fn add(self, rhs: Self) -> Self::Output {
+        Point::new(self.x + rhs.x, self.y + rhs.y)
+    }
+}
+
+impl<T: Sub<Output = T>> Sub for Point<T> {
+    type Output = Point<T>;
+    fn sub(self, rhs: Self) -> Self::Output {
+        Point::new(self.x - rhs.x, self.y - rhs.y)
+    }
+}
+
+impl<T: AddAssign> AddAssign for Point<T> {
+    fn add_assign(&mut self, rhs: Self) {
+        self.x += rhs.x;
+        self.y += rhs.y;
+    }
+}
+
+impl<T: SubAssign> SubAssign for Point<T> {
+    fn sub_assign(&mut self, rhs: Self) {
+        self.x -= rhs.x;
+        self.y -= rhs.y;
+    }
+}
+
+impl<T: Neg<Output = T>> Neg for Point<T> {
+    type Output = Point<T>;
+    fn neg(self) -> Self::Output {
+        Point::new(-self.x, -self.y)
+    }
+}
+
+impl<T: Copy + Mul<Output = T>> Mul<T> for Point<T> {
+    type Output = Point<T>;
+    fn mul(self, rhs: T) -> Self::Output {
+        Point::new(self.x * rhs, self.y * rhs)
+    }
+}
+
+impl<T: Display> Display for Point<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{},{}", self.x, self.y)
+    }
+}
+
+/// Parses `x,y`, allowing whitespace around either coordinate.
+impl<T> FromStr for Point<T>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (x, y) = s.split_once(',').ok_or_else(|| {
+            let column = s.len() - s.trim_start().len() + 1;
+            Error::parse_at(1, column, format!("invalid point '{}'", s.trim())).with_text(s.trim())
+        })?;
+
+        let coord = |name: &str, v: &str, offset: usize| {
+            let column = offset + v.len() - v.trim_start().len() + 1;
+            v.trim().parse::<T>().map_err(|e| {
+                Error::parse_at(
+                    1,
+                    column,
+                    format!("cannot parse {} '{}': {}", name, v.trim(), e),
+                )
+                .with_text(v.trim())
+            })
+        };
+
+        let x = coord("x", x, 0)?;
+        let y = coord("y", y, s.len() - y.len())?;
+
+        Ok(Point { x, y })
+    }
+}
+
+/// The smallest axis-aligned rectangle containing a set of points. Both corners
+/// are inclusive.
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Hash)]
+pub struct BoundingBox<T = i64> {
+    pub min: Point<T>,
+    pub max: Point<T>,
+}
+
+impl<T: Coord> BoundingBox<T> {
+    /// Returns `None` if `points` is empty.
+    pub fn of<I: IntoIterator<Item = Point<T>>>(points: I) -> Option<BoundingBox<T>> {
+        let mut points = points.into_iter();
+        let first = points.next()?;
+        let mut bb = BoundingBox {
+            min: first,
+            max: first,
+        };
+
+        points.for_each(|p| bb.extend(p));
+        Some(bb)
+    }
+
+    pub fn extend(&mut self, p: Point<T>) {
+        self.min = Point::new(self.min.x.min(p.x), self.min.y.min(p.y));
+        self.max = Point::new(self.max.x.max(p.x), self.max.y.max(p.y));
+    }
+
+    pub fn contains(&self, p: &Point<T>) -> bool {
+        (self.min.x..=self.max.x).contains(&p.x) && (self.min.y..=self.max.y).contains(&p.y)
+    }
+
+    pub fn width(&self) -> T {
+        self.max.x - self.min.x + T::ONE
+    }
+
+    pub fn height(&self) -> T {
+        self.max.y - self.min.y + T::ONE
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn point_from_str() {
+        assert_eq!(" 1, -2".parse::<Point>(), Ok(Point::new(1, -2)));
+        assert_eq!("3,4".parse::<Point<usize>>(), Ok(Point::new(3, 4)));
+        assert_eq!(
+            "1;2".parse::<Point>(),
+            Err(Error::parse_at(1, 1, "invalid point '1;2'").with_text("1;2"))
+        );
+        assert_eq!(
+            "1,-2".parse::<Point<u8>>(),
+            Err(
+                Error::parse_at(1, 3, "cannot parse y '-2': invalid digit found in string")
+                    .with_text("-2")
+            )
+        );
+    }
+
+    #[test]
+    fn distances() {
+        let (a, b) = (Point::new(1, 5), Point::new(4, 1));
+        assert_eq!(a.manhattan(&b), 7);
+        assert_eq!(a.chebyshev(&b), 4);
+
+        let (a, b) = (Point::<u32>::new(1, 5), Point::new(4, 1));
+        assert_eq!(b.manhattan(&a), 7);
+        assert_eq!(b.chebyshev(&a), 4);
+    }
+
+    #[test]
+    fn rotations() {
+        let right = Vector::new(1, 0);
+
+        assert_eq!(right.rotate_right(), Vector::new(0, 1));
+        assert_eq!(right.rotate_left(), Vector::new(0, -1));
+        assert_eq!(right.rotate_right().rotate_right(), -right);
+        assert_eq!(
+            Point::new(2, 3).rotate_left().rotate_right(),
+            Point::new(2, 3)
+        );
+    }
+
+    #[test]
+    fn products() {
+        let (a, b) = (Point::new(2, 3), Point::new(-1, 4));
+
+        assert_eq!(a.dot(&b), 10);
+        assert_eq!(a.cross(&b), 11);
+        assert_eq!(b.cross(&a), -11);
+        assert_eq!(a.cross(&(a * 3)), 0);
+    }
+
+    #[test]
+    fn unit() {
+        assert_eq!(Vector::new(3, -2).unit(), Vector::new(1, -1));
+        assert_eq!(Vector::new(0, 7).unit(), Vector::new(0, 1));
+        assert_eq!(Vector::new(0, 0).unit(), Vector::new(0, 0));
+    }
+
+    #[test]
+    fn neighbors() {
+        assert_eq!(
+            Point::new(1, 1).neighbors4().collect::<Vec<_>>(),
+            vec![
+                Point::new(1, 0),
+                Point::new(2, 1),
+                Point::new(1, 2),
+                Point::new(0, 1)
+            ]
+        );
+        assert_eq!(Point::new(1, 1).neighbors8().count(), 8);
+
+        assert_eq!(
+            Point::<usize>::new(0, 0).neighbors4().collect::<Vec<_>>(),
+            vec![Point::new(1, 0), Point::new(0, 1)]
+        );
+        assert_eq!(
+            Point::<usize>::new(0, 0).neighbors8().collect::<Vec<_>>(),
+            vec![Point::new(1, 0), Point::new(1, 1), Point::new(0, 1)]
+        );
+    }
+
+    #[test]
+    fn bounding_box() {
+        let bb =
+            BoundingBox::of(vec![Point::new(2, -1), Point::new(-3, 4), Point::new(0, 0)]).unwrap();
+
+        assert_eq!(bb.min, Point::new(-3, -1));
+        assert_eq!(bb.max, Point::new(2, 4));
+        assert_eq!((bb.width(), bb.height()), (6, 6));
+        assert!(bb.contains(&Point::new(2, 4)));
+        assert!(!bb.contains(&Point::new(3, 0)));
+        assert_eq!(BoundingBox::<i64>::of(vec![]), None);
+    }
+}
@@ -0,0 +1,343 @@
+//! A dense, rectangular 2D grid indexed by `Point<usize>`.
+
+use crate::error::Error;
+use crate::geom::{BoundingBox, Point};
+use std::collections::HashMap;
+use std::fmt::{self, Display};
+use std::ops::{Index, IndexMut};
+use std::str::FromStr;
+
+/// Cells are stored row by row, so `(x, y)` is at `y * width + x`.
+#[derive(PartialEq, Eq, Debug, Default, Clone, Hash)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    pub fn new(width: usize, height: usize) -> Grid<T>
+    where
+        T: Clone + Default,
+    {
+        Grid::filled(width, height, T::default())
+    }
+
+    pub fn filled(width: usize, height: usize, value: T) -> Grid<T>
+    where
+        T: Clone,
+    {
+        Grid {
+            width,
+            height,
+            cells: vec![value; width * height],
+        }
+    }
+
+    /// Fails if the rows have different lengths.
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Result<Grid<T>, Error> {
+        let width = rows.first().map_or(0, |r| r.len());
+        let height = rows.len();
+
+        if let Some(y) = rows.iter().position(|r| r.len() != width) {
+            return Err(Error::InvalidState(format!(
+                "row {} has {} cells, but row 0 has {}",
+                y,
+                rows[y].len(),
+                width
+            )));
+        }
+
+        Ok(Grid {
+            width,
+            height,
+            cells: rows.into_iter().flatten().collect(),
+        })
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn contains(&self, p: &Point<usize>) -> bool {
+        p.x < self.width && p.y < self.height
+    }
+
+    pub fn get(&self, p: &Point<usize>) -> Option<&T> {
+        if self.contains(p) {
+            Some(&self.cells[p.y * self.width + p.x])
+        } else {
+            None
+        }
+    }
+
+    pub fn get_mut(&mut self, p: &Point<usize>) -> Option<&mut T> {
+        if self.contains(p) {
+            Some(&mut self.cells[p.y * self.width + p.x])
+        } else {
+            None
+        }
+    }
+
+    /// All points of the grid, row by row.
+    pub fn points(&self) -> impl Iterator<Item = Point<usize>> {
+        let width = self.width;
+        (0..self.cells.len()).map(move |i| Point::new(i % width, i / width))
+    }
+
+    /// All cells with their points, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = (Point<usize>, &T)> {
+        self.points().zip(self.cells.iter())
+    }
+
+    /// The point of the first cell, row by row, matching `f`.
+    pub fn position<F: FnMut(&T) -> bool>(&self, f: F) -> Option<Point<usize>> {
+        let i = self.cells.iter().position(f)?;
+        Some(Point::new(i % self.width, i / self.width))
+    }
+
+    pub fn row(&self, y: usize) -> &[T] {
+        &self.cells[y * self.width..(y + 1) * self.width]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        (0..self.height).map(move |y| self.row(y))
+    }
+
+    pub fn column(&self, x: usize) -> impl Iterator<Item = &T> {
+        assert!(x < self.width, "column {} out of range", x);
+        self.cells.iter().skip(x).step_by(self.width)
+    }
+
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        (0..self.width).map(move |x| self.column(x))
+    }
+
+    /// The up to 4 orthogonally adjacent points inside the grid.
+    pub fn neighbors4(&self, p: &Point<usize>) -> impl Iterator<Item = Point<usize>> + '_ {
+        p.neighbors4().filter(move |n| self.contains(n))
+    }
+
+    /// The up to 8 orthogonally or diagonally adjacent points inside the grid.
+    pub fn neighbors8(&self, p: &Point<usize>) -> impl Iterator<Item = Point<usize>> + '_ {
+        p.neighbors8().filter(move |n| self.contains(n))
+    }
+
+    pub fn map<U, F: FnMut(&T) -> U>(&self, f: F) -> Grid<U> {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(f).collect(),
+        }
+    }
+
+    /// Swaps rows and columns, so `(x, y)` moves to `(y, x)`.
+    pub fn transpose(&self) -> Grid<T>
+    where
+        T: Clone,
+    {
+        Grid {
+            width: self.height,
+            height: self.width,
+            cells: self.columns().flatten().cloned().collect(),
+        }
+    }
+
+    /// Builds a grid covering the bounding box of a sparse map, such as a
+    /// histogram of points. Returns the grid and the point its `(0, 0)`
+    /// corresponds to; cells missing from the map are `T::default()`.
+    pub fn from_sparse(map: &HashMap<Point, T>) -> (Grid<T>, Point)
+    where
+        T: Clone + Default,
+    {
+        let bb = match BoundingBox::of(map.keys().cloned()) {
+            Some(bb) => bb,
+            None => return (Grid::default(), Point::default()),
+        };
+
+        let mut grid = Grid::new(bb.width() as usize, bb.height() as usize);
+        for (p, v) in map.iter() {
+            let q = *p - bb.min;
+            grid[Point::new(q.x as usize, q.y as usize)] = v.clone();
+        }
+
+        (grid, bb.min)
+    }
+}
+
+impl<T> Index<Point<usize>> for Grid<T> {
+    type Output = T;
+    fn index(&self, p: Point<usize>) -> &Self::Output {
+        self.get(&p)
+            .unwrap_or_else(|| panic!("point {} out of range", p))
+    }
+}
+
+impl<T> IndexMut<Point<usize>> for Grid<T> {
+    fn index_mut(&mut self, p: Point<usize>) -> &mut Self::Output {
+        self.get_mut(&p)
+            .unwrap_or_else(|| panic!("point {} out of range", p))
+    }
+}
+
+/// Prints the rows on separate lines, with no separator between cells.
+impl<T: Display> Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in self.rows() {
+            for v in row {
+                write!(f, "{}", v)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+/// Parses one cell per character, skipping empty lines.
+fn parse_cells<T, F>(s: &str, what: &str, f: F) -> Result<Grid<T>, Error>
+where
+    F: Fn(char) -> Option<T>,
+{
+    let mut rows: Vec<Vec<T>> = vec![];
+
+    for (n, line) in s.lines().enumerate().filter(|(_, l)| !l.is_empty()) {
+        let row = line
+            .char_indices()
+            .map(|(i, c)| {
+                f(c).ok_or_else(|| {
+                    Error::parse_at(n + 1, i + 1, format!("invalid {} '{}'", what, c)).with_text(c)
+                })
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        if let Some(first) = rows.first() {
+            if row.len() != first.len() {
+                let msg = format!("expected {} columns, found {}", first.len(), row.len());
+                return Err(Error::parse_at(n + 1, 1, msg).with_text(line));
+            }
+        }
+        rows.push(row);
+    }
+
+    Grid::from_rows(rows)
+}
+
+/// Parses a map of single digits, e.g. a height map.
+impl FromStr for Grid<u8> {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_cells(s, "digit", |c| c.to_digit(10).map(|d| d as u8))
+    }
+}
+
+/// Parses a map of characters, e.g. `#` and `.` for walls and floor.
+impl FromStr for Grid<char> {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_cells(s, "character", Some)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn digits_from_str() {
+        let grid = "123\n456\n".parse::<Grid<u8>>().unwrap();
+
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid[Point::new(0, 0)], 1);
+        assert_eq!(grid[Point::new(2, 1)], 6);
+        assert_eq!(grid.get(&Point::new(3, 0)), None);
+        assert_eq!(grid.to_string(), "123\n456\n");
+    }
+
+    #[test]
+    fn from_str_error() {
+        assert_eq!(
+            "123\n4x6".parse::<Grid<u8>>(),
+            Err(Error::parse_at(2, 2, "invalid digit 'x'").with_text("x"))
+        );
+        assert_eq!(
+            "#.#\n##".parse::<Grid<char>>(),
+            Err(Error::parse_at(2, 1, "expected 3 columns, found 2").with_text("##"))
+        );
+    }
+
+    #[test]
+    fn rows_and_columns() {
+        let grid = "#..\n.#.".parse::<Grid<char>>().unwrap();
+
+        assert_eq!(
+            grid.rows().collect::<Vec<_>>(),
+            vec![&['#', '.', '.'][..], &['.', '#', '.'][..]]
+        );
+        assert_eq!(grid.column(1).collect::<String>(), ".#");
+        assert_eq!(
+            grid.columns()
+                .map(|c| c.collect::<String>())
+                .collect::<Vec<_>>(),
+            vec!["#.", ".#", ".."]
+        );
+        assert_eq!(grid.position(|&c| c == '#'), Some(Point::new(0, 0)));
+        assert_eq!(
+            grid.iter()
+                .filter(|(_, &c)| c == '#')
+                .map(|(p, _)| p)
+                .last(),
+            Some(Point::new(1, 1))
+        );
+    }
+
+    #[test]
+    fn transpose() {
+        let grid = "123\n456".parse::<Grid<u8>>().unwrap();
+        let t = grid.transpose();
+
+        assert_eq!((t.width(), t.height()), (2, 3));
+        assert_eq!(t.to_string(), "14\n25\n36\n");
+        assert_eq!(t.transpose(), grid);
+    }
+
+    #[test]
+    fn neighbors() {
+        let grid: Grid<u8> = Grid::new(3, 2);
+
+        assert_eq!(
+            grid.neighbors4(&Point::new(0, 0)).collect::<Vec<_>>(),
+            vec![Point::new(1, 0), Point::new(0, 1)]
+        );
+        assert_eq!(grid.neighbors8(&Point::new(1, 1)).count(), 5);
+        assert_eq!(grid.neighbors8(&Point::new(2, 1)).count(), 3);
+    }
+
+    #[test]
+    fn from_sparse() {
+        let map: HashMap<Point, usize> = vec![(Point::new(-1, 2), 1), (Point::new(1, 3), 2)]
+            .into_iter()
+            .collect();
+        let (grid, origin) = Grid::from_sparse(&map);
+
+        assert_eq!(origin, Point::new(-1, 2));
+        assert_eq!(grid.to_string(), "100\n002\n");
+        assert_eq!(
+            Grid::<usize>::from_sparse(&HashMap::new()),
+            (Grid::default(), Point::default())
+        );
+    }
+
+    #[test]
+    fn from_rows_error() {
+        assert!(matches!(
+            Grid::from_rows(vec![vec![1, 2], vec![3]]),
+            Err(Error::InvalidState(_))
+        ));
+    }
+}
@@ -0,0 +1,65 @@
+use crate::error::Error;
+use crate::solution::Key;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+/// The conventional location of the puzzle input, e.g. `inputs/2021/04.txt`.
+pub fn default_path(key: &Key) -> PathBuf {
+    Path::new("inputs")
+        .join(key.year.to_string())
+        .join(format!("{:02}.txt", key.day))
+}
+
+/// Whether there is no input at `path`. Stdin, given as `-`, always counts as
+/// present.
+pub fn is_missing(path: &Path) -> bool {
+    path != Path::new("-") && !path.exists()
+}
+
+/// Reads the puzzle input from `path`, or from stdin when `path` is `-`.
+pub fn read(path: &Path) -> Result<String, Error> {
+    let mut src = String::new();
+
+    if path == Path::new("-") {
+        std::io::stdin()
+            .read_to_string(&mut src)
+            .map_err(|e| Error::Io(e.kind(), format!("cannot read input from stdin: {}", e)))?;
+    } else {
+        src = std::fs::read_to_string(path).map_err(|e| {
+            Error::Io(
+                e.kind(),
+                format!("cannot read input '{}': {}", path.display(), e),
+            )
+        })?;
+    }
+
+    Ok(src)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_default_path() {
+        assert_eq!(
+            default_path(&Key::new(2021, 4)),
+            Path::new("inputs/2021/04.txt")
+        );
+        assert_eq!(
+            default_path(&Key::new(2021, 25)),
+            Path::new("inputs/2021/25.txt")
+        );
+    }
+
+    #[test]
+    fn read_missing_file() {
+        let err = read(Path::new("inputs/0/00.txt")).unwrap_err();
+        assert!(matches!(err, Error::Io(std::io::ErrorKind::NotFound, _)));
+        assert!(err
+            .to_string()
+            .starts_with("cannot read input 'inputs/0/00.txt'"));
+        assert!(is_missing(Path::new("inputs/0/00.txt")));
+        assert!(!is_missing(Path::new("-")));
+    }
+}
@@ -0,0 +1,141 @@
+//! Iterator adaptors shared by the solutions.
+
+use std::iter::Sum;
+
+pub trait IterExt: Iterator + Sized {
+    /// Yields every run of `N` consecutive items, oldest first, e.g.
+    /// `[1, 2, 3, 4]` gives `[1, 2, 3]` and `[2, 3, 4]`. Yields nothing if
+    /// there are fewer than `N` items.
+    ///
+    /// # Panics
+    ///
+    /// If `N` is 0.
+    fn windows<const N: usize>(self) -> Windows<Self, N>
+    where
+        Self::Item: Copy,
+    {
+        assert!(N > 0, "window size must be positive");
+        Windows {
+            iter: self,
+            buf: None,
+        }
+    }
+
+    /// Yields every pair of consecutive items, e.g. `[1, 2, 3]` gives `(1, 2)`
+    /// and `(2, 3)`.
+    fn pairwise(self) -> impl Iterator<Item = (Self::Item, Self::Item)>
+    where
+        Self::Item: Copy,
+    {
+        self.windows::<2>().map(|[a, b]| (a, b))
+    }
+
+    /// The sum of every window of `N` consecutive items.
+    fn rolling_sum<const N: usize>(self) -> impl Iterator<Item = Self::Item>
+    where
+        Self::Item: Copy + Sum,
+    {
+        self.windows::<N>().map(|w| w.into_iter().sum())
+    }
+
+    /// The minimum of every window of `N` consecutive items.
+    fn rolling_min<const N: usize>(self) -> impl Iterator<Item = Self::Item>
+    where
+        Self::Item: Copy + Ord,
+    {
+        self.windows::<N>().map(|w| w.into_iter().min().unwrap())
+    }
+
+    /// The maximum of every window of `N` consecutive items.
+    fn rolling_max<const N: usize>(self) -> impl Iterator<Item = Self::Item>
+    where
+        Self::Item: Copy + Ord,
+    {
+        self.windows::<N>().map(|w| w.into_iter().max().unwrap())
+    }
+}
+
+impl<I: Iterator> IterExt for I {}
+
+/// The iterator returned by [`IterExt::windows`].
+pub struct Windows<I: Iterator, const N: usize> {
+    iter: I,
+    buf: Option<[I::Item; N]>,
+}
+
+impl<I, const N: usize> Iterator for Windows<I, N>
+where
+    I: Iterator,
+    I::Item: Copy,
+{
+    type Item = [I::Item; N];
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match &mut self.buf {
+            Some(buf) => {
+                let v = self.iter.next()?;
+                buf.rotate_left(1);
+                buf[N - 1] = v;
+                Some(*buf)
+            }
+            None => {
+                let first: Vec<_> = self.iter.by_ref().take(N).collect();
+                let buf: [I::Item; N] = first.try_into().ok()?;
+                self.buf = Some(buf);
+                Some(buf)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn windows() {
+        assert_eq!(
+            (1..=5).windows::<3>().collect::<Vec<_>>(),
+            vec![[1, 2, 3], [2, 3, 4], [3, 4, 5]]
+        );
+        assert_eq!(
+            "abc".chars().windows::<1>().collect::<Vec<_>>(),
+            vec![['a'], ['b'], ['c']]
+        );
+        assert_eq!((1..=2).windows::<3>().count(), 0);
+        assert_eq!((1..=3).windows::<3>().count(), 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "window size must be positive")]
+    fn windows_empty() {
+        let _ = (1..=3).windows::<0>();
+    }
+
+    #[test]
+    fn pairwise() {
+        assert_eq!(
+            [3, 1, 2].into_iter().pairwise().collect::<Vec<_>>(),
+            vec![(3, 1), (1, 2)]
+        );
+        assert_eq!(std::iter::once(1).pairwise().count(), 0);
+    }
+
+    #[test]
+    fn rolling() {
+        let v = [4, 1, 3, 5, 2];
+
+        assert_eq!(
+            v.into_iter().rolling_sum::<3>().collect::<Vec<_>>(),
+            vec![8, 9, 10]
+        );
+        assert_eq!(
+            v.into_iter().rolling_min::<2>().collect::<Vec<_>>(),
+            vec![1, 1, 3, 2]
+        );
+        assert_eq!(
+            v.into_iter().rolling_max::<2>().collect::<Vec<_>>(),
+            vec![4, 3, 5, 5]
+        );
+    }
+}
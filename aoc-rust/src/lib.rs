@@ -0,0 +1,40 @@
+//! Advent of Code solutions and the utilities shared between them.
+//!
+//! Every day is exposed as `yYYYY::dayNN::DayNN`, implementing [`Solution`],
+//! and [`registry`] maps each [`Key`] to its solution.
+
+pub mod answers;
+pub mod bench;
+pub mod bigint;
+pub mod bits;
+pub mod error;
+pub mod geom;
+pub mod grid;
+pub mod input;
+pub mod iter;
+pub mod matrix;
+pub mod memo;
+pub mod parse;
+pub mod runner;
+pub mod scaffold;
+pub mod solution;
+
+pub use error::Error;
+pub use solution::{DynSolution, Key, Part, Solution};
+
+use std::collections::BTreeMap;
+
+pub type Registry = BTreeMap<Key, Box<dyn DynSolution>>;
+
+macro_rules! make_map {
+    ($($year:expr, $day:expr => $s:expr),+ $(,)?) => {
+        {
+            let mut m: Registry = BTreeMap::new();
+            $(m.insert(Key::new($year, $day), Box::new($s));)+
+            m
+        }
+    };
+}
+
+// Declares the `yYYYY` modules and `registry()`, generated by build.rs.
+include!(concat!(env!("OUT_DIR"), "/registry.rs"));
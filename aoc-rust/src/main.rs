@@ -1,48 +1,320 @@
-mod y2021;
+mod cli;
 
-use std::{collections::HashMap, env};
+use aoc_rust::answers::Answers;
+use aoc_rust::bench::{self, format_duration, Stats};
+use aoc_rust::runner::{self, Status};
+use aoc_rust::{input, scaffold, Error, Key, Part, Registry};
+use cli::{Command, Format, Selection, USAGE};
+use std::path::Path;
+use std::process::exit;
+use std::time::Duration;
 
 fn main() {
-    let m = get_map();
-    if let Some(p) = env::args().nth(1) {
-        let solve = m
-            .get(&p)
-            .expect(&format!("problem '{}' is not found", &p).to_owned());
-        solve();
-    } else {
-        println!("Available problems:");
-        for k in m.keys() {
-            println!("{}", k);
+    let command = match Command::parse(std::env::args().skip(1)) {
+        Ok(c) => c,
+        Err(e) => {
+            eprintln!("error: {}\n\n{}", e, USAGE);
+            exit(2);
+        }
+    };
+
+    let mut m = aoc_rust::registry();
+    let result = configure(&mut m, &command).and_then(|()| match command {
+        Command::Help => {
+            println!("{}", USAGE);
+            Ok(0)
+        }
+        Command::List => {
+            for k in m.keys() {
+                println!("{}", k);
+            }
+            Ok(0)
+        }
+        Command::Run(sel, format) => run(&m, &sel, format),
+        Command::All(sel, format) => all(&m, &sel, format),
+        Command::Verify(sel, path) => verify(&m, &sel, &path),
+        Command::Bench(sel, runs) => bench(&m, &sel, runs),
+        Command::New(key) => new(&key),
+    });
+
+    match result {
+        Ok(0) => {}
+        Ok(code) => exit(code),
+        Err(e) => {
+            eprintln!("error: {}", e);
+            exit(e.exit_code());
         }
     }
 }
 
-macro_rules! make_map {
-    ($($name:expr => $f:expr),+) => {
-        {
-            use std::collections::HashMap;
-            let mut m:HashMap<String, fn()>  = HashMap::new();
-            $(m.insert($name.to_string(), $f as fn());)+
-            m
-        }
+fn keys(m: &Registry) -> Vec<Key> {
+    m.keys().cloned().collect()
+}
+
+fn resolve(m: &Registry, sel: &Selection) -> Result<Vec<(Key, Part)>, Error> {
+    sel.resolve(&keys(m)).map_err(Error::Usage)
+}
+
+/// Applies the `--opt` options to the selected solution.
+fn configure(m: &mut Registry, command: &Command) -> Result<(), Error> {
+    let sel = match command.selection() {
+        Some(sel) if !sel.options.is_empty() => sel,
+        _ => return Ok(()),
     };
+
+    for (key, _) in cli::group_by_day(&resolve(m, sel)?) {
+        let solution = m.get_mut(&key).expect("resolved keys are registered");
+        for (k, v) in sel.options.iter() {
+            solution
+                .set_option(k, v)
+                .map_err(|e| Error::Usage(format!("{}: {}", key, e)))?;
+        }
+    }
+    Ok(())
 }
 
-fn get_map() -> HashMap<String, fn()> {
-    return make_map! (
-        "y2021/day01" => y2021::day01::solve,
-        "y2021/day01_2" => y2021::day01_2::solve,
-        "y2021/day02" => y2021::day02::solve,
-        "y2021/day02_2" => y2021::day02_2::solve,
-        "y2021/day03" => y2021::day03::solve,
-        "y2021/day03_2" => y2021::day03_2::solve,
-        "y2021/day04" => y2021::day04::solve,
-        "y2021/day04_2" => y2021::day04::solve_part2,
-        "y2021/day05" => y2021::day05::solve,
-        "y2021/day05_2" => y2021::day05::solve_part2,
-        "y2021/day06" => y2021::day06::solve,
-        "y2021/day06_2" => y2021::day06::solve_part2,
-        "y2021/day07" => y2021::day07::solve,
-        "y2021/day07_2" => y2021::day07::solve_part2
+/// Runs the selected solutions and prints their answers. Returns the exit code
+/// of the first error, if any of them could not be solved.
+fn run(m: &Registry, sel: &Selection, format: Format) -> Result<i32, Error> {
+    let targets = resolve(m, sel)?;
+    let mut code = 0;
+
+    for (key, parts) in cli::group_by_day(&targets) {
+        for o in runner::run_day(m[&key].as_ref(), key, &parts, &sel.input_path(&key)) {
+            if let (0, Some(e)) = (code, &o.error) {
+                code = e.exit_code();
+            }
+
+            if format == Format::Json {
+                println!("{}", o.to_json());
+                continue;
+            }
+
+            match (&o.answer, &o.error) {
+                (Some(ans), _) if targets.len() == 1 => println!("{}", ans),
+                (Some(ans), _) => println!("{}: {}", o.label(), ans),
+                (None, Some(e)) => {
+                    eprintln!("error: {}: {}", o.label(), e);
+                    if let Some(snippet) = &o.snippet {
+                        eprintln!("{}", snippet);
+                    }
+                }
+                (None, None) => {}
+            }
+        }
+    }
+
+    Ok(code)
+}
+
+/// Runs the selected solutions in order and prints the answer and elapsed
+/// time of each, followed by the total.
+fn all(m: &Registry, sel: &Selection, format: Format) -> Result<i32, Error> {
+    let targets = resolve(m, sel)?;
+    let (mut solved, mut failed, mut missing) = (0, 0, 0);
+    let mut total = Duration::ZERO;
+
+    if format == Format::Text {
+        println!("{:<20} {:<20} {:>10}", "problem", "answer", "time");
+    }
+    for (key, parts) in cli::group_by_day(&targets) {
+        for o in runner::run_day(m[&key].as_ref(), key, &parts, &sel.input_path(&key)) {
+            if format == Format::Json {
+                println!("{}", o.to_json());
+                failed += (o.status == Status::Failed) as usize;
+                continue;
+            }
+
+            let (answer, time) = match o.status {
+                Status::Solved => {
+                    solved += 1;
+                    total += o.elapsed;
+                    (
+                        o.answer.clone().unwrap_or_default(),
+                        format_duration(o.elapsed),
+                    )
+                }
+                Status::Failed => {
+                    failed += 1;
+                    ("(failed)".to_string(), "-".to_string())
+                }
+                Status::Missing => {
+                    missing += 1;
+                    ("(missing)".to_string(), "-".to_string())
+                }
+            };
+            println!("{:<20} {:<20} {:>10}", o.label(), answer, time);
+
+            if let (Status::Failed, Some(e)) = (o.status, &o.error) {
+                eprintln!("{}: {}", o.label(), e);
+                if let Some(snippet) = &o.snippet {
+                    eprintln!("{}", snippet);
+                }
+            }
+        }
+    }
+
+    if format == Format::Text {
+        println!(
+            "{:<41} {:>10}",
+            format!(
+                "total: {} solved, {} failed, {} missing",
+                solved, failed, missing
+            ),
+            format_duration(total)
+        );
+    }
+
+    Ok(if failed == 0 { 0 } else { 1 })
+}
+
+/// Runs the selected solutions against their inputs and compares the results
+/// with the known answers. Exits with 1 if any answer regressed.
+fn verify(m: &Registry, sel: &Selection, path: &Path) -> Result<i32, Error> {
+    let targets = resolve(m, sel)?;
+    let answers = input::read(path)?.parse::<Answers>()?;
+    let (mut passed, mut failed, mut missing) = (0, 0, 0);
+
+    for (key, parts) in cli::group_by_day(&targets) {
+        let (known, unknown): (Vec<_>, Vec<_>) = parts
+            .into_iter()
+            .partition(|part| answers.get(&key, *part).is_some());
+
+        for part in unknown {
+            println!(
+                "{} part {}: missing (no recorded answer)",
+                key,
+                part.number()
+            );
+            missing += 1;
+        }
+
+        for o in runner::run_day(m[&key].as_ref(), key, &known, &sel.input_path(&key)) {
+            let expected = answers.get(&key, o.part).unwrap();
+            let error = o.error.as_ref().map(|e| e.to_string()).unwrap_or_default();
+
+            match (o.status, o.answer.clone()) {
+                (Status::Solved, Some(ans)) if ans == expected => {
+                    println!("{}: pass", o.label());
+                    passed += 1;
+                }
+                (Status::Solved, ans) => {
+                    println!(
+                        "{}: FAIL (expected {}, got {})",
+                        o.label(),
+                        expected,
+                        ans.unwrap_or_default()
+                    );
+                    failed += 1;
+                }
+                (Status::Failed, _) => {
+                    println!("{}: FAIL ({})", o.label(), error);
+                    if let Some(snippet) = &o.snippet {
+                        println!("{}", snippet);
+                    }
+                    failed += 1;
+                }
+                (Status::Missing, _) => {
+                    println!("{}: missing ({})", o.label(), error);
+                    missing += 1;
+                }
+            }
+        }
+    }
+
+    println!("{} passed, {} failed, {} missing", passed, failed, missing);
+    Ok(if failed == 0 { 0 } else { 1 })
+}
+
+/// Times parsing and solving of the selected solutions and prints a table,
+/// slowest first, followed by the parts that failed.
+fn bench(m: &Registry, sel: &Selection, runs: usize) -> Result<i32, Error> {
+    let targets = resolve(m, sel)?;
+
+    let mut rows: Vec<(String, Stats, Stats)> = vec![];
+    let mut failures: Vec<(String, Error, Option<String>)> = vec![];
+
+    for (key, parts) in cli::group_by_day(&targets) {
+        let path = sel.input_path(&key);
+        if input::is_missing(&path) {
+            eprintln!("{}: skipped (no input at {})", key, path.display());
+            continue;
+        }
+
+        let label = |part: Part| format!("{} part {}", key, part.number());
+        let src = match input::read(&path) {
+            Ok(src) => src,
+            Err(e) => {
+                failures.extend(parts.iter().map(|p| (label(*p), e.clone(), None)));
+                continue;
+            }
+        };
+
+        let (parsed, samples) = bench::measure(runs, || m[&key].parse(&src));
+        let parsed = match parsed.unwrap() {
+            Ok(parsed) => parsed,
+            Err(e) => {
+                let snippet = e.snippet(&src);
+                failures.extend(
+                    parts
+                        .iter()
+                        .map(|p| (label(*p), e.clone(), snippet.clone())),
+                );
+                continue;
+            }
+        };
+        let parse_stats = Stats::from_samples(&samples).unwrap();
+
+        for part in parts {
+            let (result, samples) = bench::measure(runs, || m[&key].run(parsed.as_ref(), part));
+            if let Err(e) = result
+                .unwrap()
+                .and_then(|_| m[&key].export(parsed.as_ref(), part))
+            {
+                failures.push((label(part), e, None));
+                continue;
+            }
+            let solve_stats = Stats::from_samples(&samples).unwrap();
+
+            rows.push((label(part), parse_stats, solve_stats));
+        }
+    }
+
+    rows.sort_by_key(|(_, p, s)| std::cmp::Reverse(p.median + s.median));
+
+    println!(
+        "{:<20} {:>10} {:>10} {:>10} {:>10} {:>10} {:>10}",
+        "problem", "parse min", "median", "max", "solve min", "median", "max"
     );
+    for (label, p, s) in rows.iter() {
+        println!(
+            "{:<20} {:>10} {:>10} {:>10} {:>10} {:>10} {:>10}",
+            label,
+            format_duration(p.min),
+            format_duration(p.median),
+            format_duration(p.max),
+            format_duration(s.min),
+            format_duration(s.median),
+            format_duration(s.max),
+        );
+    }
+    for (label, e, snippet) in failures.iter() {
+        println!("{:<20} {:>10}", label, "(failed)");
+        eprintln!("{}: {}", label, e);
+        if let Some(snippet) = snippet {
+            eprintln!("{}", snippet);
+        }
+    }
+
+    Ok(if failures.is_empty() { 0 } else { 1 })
+}
+
+/// Creates the source file and an empty input for a new day. The build script
+/// registers the new file on the next build.
+fn new(key: &Key) -> Result<i32, Error> {
+    let src_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("src");
+    for path in scaffold::create(&src_dir, Path::new("inputs"), key)? {
+        println!("created {}", path.display());
+    }
+    Ok(0)
 }
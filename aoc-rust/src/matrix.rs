@@ -0,0 +1,345 @@
+//! Dense matrices with fast exponentiation, for linear recurrences such as
+//! population counts after a huge number of steps.
+
+use std::fmt::{self, Display};
+use std::ops::{Add, Index, IndexMut, Mul};
+
+/// A number matrices can hold.
+pub trait Scalar: Clone + Add<Output = Self> + Mul<Output = Self> {
+    fn zero() -> Self;
+    fn one() -> Self;
+}
+
+macro_rules! impl_scalar {
+    ($($t:ty),+) => {
+        $(impl Scalar for $t {
+            fn zero() -> Self {
+                0
+            }
+
+            fn one() -> Self {
+                1
+            }
+        })+
+    };
+}
+
+impl_scalar!(i32, i64, i128, u32, u64, u128);
+
+/// An integer modulo `M`, for answers too large for any fixed-width type.
+#[derive(PartialEq, Eq, Debug, Default, Clone, Copy, Hash)]
+pub struct Mod<const M: u64>(u64);
+
+impl<const M: u64> Mod<M> {
+    pub fn new(v: u64) -> Mod<M> {
+        Mod(v % M)
+    }
+
+    pub fn value(&self) -> u64 {
+        self.0
+    }
+}
+
+impl<const M: u64> From<u64> for Mod<M> {
+    fn from(v: u64) -> Self {
+        Mod::new(v)
+    }
+}
+
+impl<const M: u64> Add for Mod<M> {
+    type Output = Mod<M>;
+    fn add(self, rhs: Self) -> Self::Output {
+        Mod(((self.0 as u128 + rhs.0 as u128) % M as u128) as u64)
+    }
+}
+
+impl<const M: u64> Mul for Mod<M> {
+    type Output = Mod<M>;
+    fn mul(self, rhs: Self) -> Self::Output {
+        Mod((self.0 as u128 * rhs.0 as u128 % M as u128) as u64)
+    }
+}
+
+impl<const M: u64> Scalar for Mod<M> {
+    fn zero() -> Self {
+        Mod(0)
+    }
+
+    fn one() -> Self {
+        Mod::new(1)
+    }
+}
+
+impl<const M: u64> Display for Mod<M> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// An integer modulo a number chosen at run time. `zero()` and `one()` have
+/// no modulus yet and take the one of the values they are combined with, so a
+/// computation is reduced as long as its inputs are built with [`DynMod::new`].
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Hash)]
+pub struct DynMod {
+    value: u64,
+    /// 0 until a modulus is known.
+    modulus: u64,
+}
+
+impl DynMod {
+    /// # Panics
+    ///
+    /// If `modulus` is 0.
+    pub fn new(v: u64, modulus: u64) -> DynMod {
+        assert!(modulus > 0, "modulus must be positive");
+        DynMod {
+            value: v % modulus,
+            modulus,
+        }
+    }
+
+    pub fn value(&self) -> u64 {
+        self.value
+    }
+
+    fn combine(self, rhs: DynMod, value: u128) -> DynMod {
+        debug_assert!(
+            self.modulus == 0 || rhs.modulus == 0 || self.modulus == rhs.modulus,
+            "moduli must agree"
+        );
+        let modulus = self.modulus.max(rhs.modulus);
+        let value = if modulus == 0 {
+            value
+        } else {
+            value % modulus as u128
+        };
+
+        DynMod {
+            value: value as u64,
+            modulus,
+        }
+    }
+}
+
+impl Add for DynMod {
+    type Output = DynMod;
+    fn add(self, rhs: Self) -> Self::Output {
+        self.combine(rhs, self.value as u128 + rhs.value as u128)
+    }
+}
+
+impl Mul for DynMod {
+    type Output = DynMod;
+    fn mul(self, rhs: Self) -> Self::Output {
+        self.combine(rhs, self.value as u128 * rhs.value as u128)
+    }
+}
+
+impl Scalar for DynMod {
+    fn zero() -> Self {
+        DynMod {
+            value: 0,
+            modulus: 0,
+        }
+    }
+
+    fn one() -> Self {
+        DynMod {
+            value: 1,
+            modulus: 0,
+        }
+    }
+}
+
+impl Display for DynMod {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.value)
+    }
+}
+
+/// Entries are stored row by row.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Matrix<T> {
+    rows: usize,
+    cols: usize,
+    data: Vec<T>,
+}
+
+impl<T: Scalar> Matrix<T> {
+    pub fn zeros(rows: usize, cols: usize) -> Matrix<T> {
+        Matrix {
+            rows,
+            cols,
+            data: vec![T::zero(); rows * cols],
+        }
+    }
+
+    pub fn identity(n: usize) -> Matrix<T> {
+        let mut m = Matrix::zeros(n, n);
+        for i in 0..n {
+            m[(i, i)] = T::one();
+        }
+        m
+    }
+
+    /// # Panics
+    ///
+    /// If the rows have different lengths.
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Matrix<T> {
+        let cols = rows.first().map_or(0, |r| r.len());
+        assert!(
+            rows.iter().all(|r| r.len() == cols),
+            "all rows must have {} columns",
+            cols
+        );
+
+        Matrix {
+            rows: rows.len(),
+            cols,
+            data: rows.into_iter().flatten().collect(),
+        }
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    /// Raises a square matrix to the power `exp` by repeated squaring, in
+    /// O(n³ log exp) operations.
+    pub fn pow(&self, mut exp: u64) -> Matrix<T> {
+        assert_eq!(self.rows, self.cols, "only square matrices have powers");
+
+        let mut result = Matrix::identity(self.rows);
+        let mut base = self.clone();
+
+        while exp > 0 {
+            if exp & 1 == 1 {
+                result = &result * &base;
+            }
+            exp >>= 1;
+            if exp > 0 {
+                base = &base * &base;
+            }
+        }
+        result
+    }
+
+    /// Multiplies by a column vector.
+    pub fn apply(&self, v: &[T]) -> Vec<T> {
+        assert_eq!(self.cols, v.len(), "vector length must match the columns");
+
+        (0..self.rows)
+            .map(|r| {
+                (0..self.cols).fold(T::zero(), |acc, c| {
+                    acc + self[(r, c)].clone() * v[c].clone()
+                })
+            })
+            .collect()
+    }
+}
+
+impl<T: Scalar> Mul for &Matrix<T> {
+    type Output = Matrix<T>;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        assert_eq!(self.cols, rhs.rows, "matrix dimensions must agree");
+
+        let mut m: Matrix<T> = Matrix::zeros(self.rows, rhs.cols);
+        for r in 0..self.rows {
+            for k in 0..self.cols {
+                let a = &self[(r, k)];
+                for c in 0..rhs.cols {
+                    m[(r, c)] = m[(r, c)].clone() + a.clone() * rhs[(k, c)].clone();
+                }
+            }
+        }
+        m
+    }
+}
+
+impl<T> Index<(usize, usize)> for Matrix<T> {
+    type Output = T;
+    fn index(&self, (r, c): (usize, usize)) -> &Self::Output {
+        assert!(
+            r < self.rows && c < self.cols,
+            "({}, {}) out of range",
+            r,
+            c
+        );
+        &self.data[r * self.cols + c]
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Matrix<T> {
+    fn index_mut(&mut self, (r, c): (usize, usize)) -> &mut Self::Output {
+        assert!(
+            r < self.rows && c < self.cols,
+            "({}, {}) out of range",
+            r,
+            c
+        );
+        &mut self.data[r * self.cols + c]
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn multiply() {
+        let a = Matrix::from_rows(vec![vec![1, 2, 3], vec![4, 5, 6]]);
+        let b = Matrix::from_rows(vec![vec![7, 8], vec![9, 10], vec![11, 12]]);
+
+        assert_eq!(
+            &a * &b,
+            Matrix::from_rows(vec![vec![58, 64], vec![139, 154]])
+        );
+        assert_eq!(a.apply(&[1, 0, -1]), vec![-2, -2]);
+        assert_eq!(&a * &Matrix::identity(3), a);
+    }
+
+    #[test]
+    fn fibonacci() {
+        let fib = Matrix::from_rows(vec![vec![1_u128, 1], vec![1, 0]]);
+
+        assert_eq!(fib.pow(0), Matrix::identity(2));
+        assert_eq!(fib.pow(10)[(0, 1)], 55);
+        assert_eq!(fib.pow(150)[(0, 1)], 9969216677189303386214405760200);
+    }
+
+    #[test]
+    fn modular() {
+        type P = Mod<1_000_000_007>;
+
+        let fib = Matrix::from_rows(vec![vec![P::new(1), P::new(1)], vec![P::new(1), P::new(0)]]);
+        assert_eq!(fib.pow(10)[(0, 1)], P::new(55));
+        assert_eq!(
+            fib.pow(150)[(0, 1)],
+            P::new((9969216677189303386214405760200_u128 % 1_000_000_007) as u64)
+        );
+        assert_eq!(
+            fib.pow(1_000_000_000_000_000)[(0, 1)].to_string(),
+            "648325137"
+        );
+    }
+
+    #[test]
+    fn dynamic_modulus() {
+        let m = 1_000_000_007;
+        let one = DynMod::new(1, m);
+        let fib = Matrix::from_rows(vec![vec![one, one], vec![one, DynMod::zero()]]);
+
+        assert_eq!(fib.pow(10)[(0, 1)].value(), 55);
+        assert_eq!(
+            fib.pow(1_000_000_000_000_000)[(0, 1)].to_string(),
+            "648325137"
+        );
+        assert_eq!((DynMod::new(6, 7) + DynMod::one()).value(), 0);
+        assert_eq!((DynMod::new(3, 7) * DynMod::new(5, 7)).value(), 1);
+    }
+}
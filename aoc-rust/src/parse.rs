@@ -0,0 +1,308 @@
+//! Helpers for the shapes puzzle inputs usually come in.
+//!
+//! Every helper reports errors located in the string it was given, and the
+//! slices it returns point into that string, so [`within`] can relocate errors
+//! found while parsing them further.
+
+use crate::error::Error;
+use std::fmt::Display;
+use std::str::FromStr;
+
+/// The 1-based line and byte column at which `sub` starts in `src`.
+///
+/// `sub` must be a slice of `src`, such as one returned by `str::lines`.
+pub fn locate(src: &str, sub: &str) -> (usize, usize) {
+    let offset = (sub.as_ptr() as usize)
+        .checked_sub(src.as_ptr() as usize)
+        .filter(|&o| o <= src.len())
+        .expect("sub must be a slice of src");
+    let before = &src[..offset];
+    let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+
+    (before.matches('\n').count() + 1, offset - line_start + 1)
+}
+
+/// Relocates an error found while parsing `sub` to its position in `src`.
+pub fn within(src: &str, sub: &str, e: Error) -> Error {
+    let (line, column) = locate(src, sub);
+    e.offset(line - 1, column - 1)
+}
+
+/// Parses `token`, a slice of `src`, reporting `invalid <what> '<token>'` at
+/// its position on failure.
+pub fn token<T>(src: &str, token: &str, what: &str) -> Result<T, Error>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    token.parse::<T>().map_err(|e| {
+        let (line, column) = locate(src, token);
+        Error::parse_at(line, column, format!("invalid {} '{}': {}", what, token, e))
+            .with_text(token)
+    })
+}
+
+/// Parses a list separated by `sep`, e.g. `3,4,3,1,2`. Items are trimmed and
+/// empty items are skipped, so a trailing separator or newline is allowed.
+pub fn list<T>(src: &str, sep: char, what: &str) -> Result<Vec<T>, Error>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    src.split(sep)
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(|s| token(src, s, what))
+        .collect()
+}
+
+/// Parses every whitespace separated word, e.g. a row of a table.
+pub fn words<T>(src: &str, what: &str) -> Result<Vec<T>, Error>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    src.split_whitespace()
+        .map(|s| token(src, s, what))
+        .collect()
+}
+
+/// Parses the whitespace separated words of each non-empty line. Rows may
+/// have different lengths.
+pub fn table<T>(src: &str, what: &str) -> Result<Vec<Vec<T>>, Error>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    src.lines()
+        .filter(|l| !l.trim().is_empty())
+        .map(|l| words(l, what).map_err(|e| within(src, l, e)))
+        .collect()
+}
+
+/// Extracts every integer in free-form text, e.g. `x=-3..5, y=10` gives
+/// `[-3, 5, 10]`. A `-` is a sign only when it is not preceded by a digit, so
+/// `1-2` gives `[1, 2]`.
+pub fn ints<T>(src: &str) -> Result<Vec<T>, Error>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    let bytes = src.as_bytes();
+    let mut nums = vec![];
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let signed = bytes[i] == b'-'
+            && bytes.get(i + 1).is_some_and(u8::is_ascii_digit)
+            && (i == 0 || !bytes[i - 1].is_ascii_digit());
+
+        if !signed && !bytes[i].is_ascii_digit() {
+            i += 1;
+            continue;
+        }
+
+        let start = i;
+        i += 1;
+        while i < bytes.len() && bytes[i].is_ascii_digit() {
+            i += 1;
+        }
+        nums.push(token(src, &src[start..i], "integer")?);
+    }
+
+    Ok(nums)
+}
+
+/// Splits `src` into blocks separated by one or more blank lines. Each block
+/// is a slice of `src` without its trailing newline.
+pub fn paragraphs(src: &str) -> Vec<&str> {
+    let mut blocks = vec![];
+    let mut current: Option<(usize, usize)> = None;
+
+    for line in src.lines() {
+        let start = line.as_ptr() as usize - src.as_ptr() as usize;
+
+        if line.trim().is_empty() {
+            if let Some((s, e)) = current.take() {
+                blocks.push(&src[s..e]);
+            }
+        } else {
+            let (s, _) = current.unwrap_or((start, start));
+            current = Some((s, start + line.len()));
+        }
+    }
+
+    if let Some((s, e)) = current {
+        blocks.push(&src[s..e]);
+    }
+    blocks
+}
+
+/// Splits each non-empty line at the first `sep` into a trimmed key and value,
+/// e.g. `name: value`.
+pub fn key_values<'a>(src: &'a str, sep: &str) -> Result<Vec<(&'a str, &'a str)>, Error> {
+    src.lines()
+        .filter(|l| !l.trim().is_empty())
+        .map(|l| {
+            let (k, v) = l.split_once(sep).ok_or_else(|| {
+                let (line, column) = locate(src, l.trim_start());
+                Error::parse_at(line, column, format!("missing '{}' in '{}'", sep, l.trim()))
+                    .with_text(l.trim())
+            })?;
+            Ok((k.trim(), v.trim()))
+        })
+        .collect()
+}
+
+/// Matches `src` against a pattern in which each `{}` captures text up to the
+/// following literal, e.g. `"{},{} -> {},{}"` on `"0,9 -> 5,9"` gives
+/// `["0", "9", "5", "9"]`. Captures are trimmed, and the literals must match
+/// exactly.
+pub fn pattern<'a>(src: &'a str, pat: &str) -> Result<Vec<&'a str>, Error> {
+    let error = |at: &str, msg: String| {
+        let (line, column) = locate(src, at);
+        Error::parse_at(line, column, msg).with_text(at.lines().next().unwrap_or_default())
+    };
+
+    let mut parts = pat.split("{}");
+    let mut rest = src;
+    let mut fields = vec![];
+
+    let head = parts.next().unwrap_or_default();
+    rest = rest
+        .strip_prefix(head)
+        .ok_or_else(|| error(rest.trim_end(), format!("expected '{}'", head)))?;
+
+    for literal in parts {
+        if literal.is_empty() {
+            fields.push(rest.trim());
+            rest = &rest[rest.len()..];
+            continue;
+        }
+
+        let end = rest
+            .find(literal)
+            .ok_or_else(|| error(rest.trim(), format!("missing '{}'", literal)))?;
+        fields.push(rest[..end].trim());
+        rest = &rest[end + literal.len()..];
+    }
+
+    if !rest.trim().is_empty() {
+        let rest = rest.trim();
+        return Err(error(rest, format!("unexpected '{}'", rest)));
+    }
+    Ok(fields)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_locate() {
+        let src = "ab\ncd\nef";
+        let lines: Vec<_> = src.lines().collect();
+
+        assert_eq!(locate(src, src), (1, 1));
+        assert_eq!(locate(src, &lines[1][1..]), (2, 2));
+        assert_eq!(locate(src, lines[2]), (3, 1));
+        assert_eq!(locate("é,x", &"é,x"[3..]), (1, 4));
+        assert_eq!(
+            within(src, lines[2], Error::parse_at(1, 2, "x")),
+            Error::parse_at(3, 2, "x")
+        );
+    }
+
+    #[test]
+    fn test_list() {
+        assert_eq!(
+            list::<u8>("3,4, 3,1,2\n", ',', "timer"),
+            Ok(vec![3, 4, 3, 1, 2])
+        );
+        assert_eq!(list::<u8>("", ',', "timer"), Ok(vec![]));
+        assert_eq!(
+            list::<u8>("3,4,\n a", ',', "timer"),
+            Err(
+                Error::parse_at(2, 2, "invalid timer 'a': invalid digit found in string")
+                    .with_text("a")
+            )
+        );
+    }
+
+    #[test]
+    fn test_table() {
+        let src = "22 13\n\n 8  2\n";
+        assert_eq!(
+            table::<u8>(src, "number"),
+            Ok(vec![vec![22, 13], vec![8, 2]])
+        );
+        assert_eq!(
+            table::<u8>("1 2\n3 x4", "number"),
+            Err(
+                Error::parse_at(2, 3, "invalid number 'x4': invalid digit found in string")
+                    .with_text("x4")
+            )
+        );
+    }
+
+    #[test]
+    fn test_ints() {
+        assert_eq!(ints::<i64>("x=-3..5, y=10"), Ok(vec![-3, 5, 10]));
+        assert_eq!(ints::<i64>("1-2 -"), Ok(vec![1, 2]));
+        assert_eq!(
+            ints::<u8>("a 1\nb 300"),
+            Err(Error::parse_at(
+                2,
+                3,
+                "invalid integer '300': number too large to fit in target type"
+            )
+            .with_text("300"))
+        );
+    }
+
+    #[test]
+    fn test_paragraphs() {
+        let src = "7,4\n\n1 2\n3 4\n\n\n5 6\n";
+        let blocks = paragraphs(src);
+
+        assert_eq!(blocks, vec!["7,4", "1 2\n3 4", "5 6"]);
+        assert_eq!(locate(src, blocks[2]), (7, 1));
+        assert!(paragraphs("\n\n").is_empty());
+    }
+
+    #[test]
+    fn test_key_values() {
+        assert_eq!(
+            key_values("a: 1\n\nb :two words\n", ":"),
+            Ok(vec![("a", "1"), ("b", "two words")])
+        );
+        assert_eq!(
+            key_values("a: 1\n  b 2", ":"),
+            Err(Error::parse_at(2, 3, "missing ':' in 'b 2'").with_text("b 2"))
+        );
+    }
+
+    #[test]
+    fn test_pattern() {
+        assert_eq!(
+            pattern("0,9 -> 5,9", "{},{} -> {},{}"),
+            Ok(vec!["0", "9", "5", "9"])
+        );
+        assert_eq!(
+            pattern("move 3 from 1", "move {} from {}"),
+            Ok(vec!["3", "1"])
+        );
+        assert_eq!(
+            pattern("0,9 > 5,9", "{} -> {}"),
+            Err(Error::parse_at(1, 1, "missing ' -> '").with_text("0,9 > 5,9"))
+        );
+        assert_eq!(
+            pattern("mv 3", "move {}"),
+            Err(Error::parse_at(1, 1, "expected 'move '").with_text("mv 3"))
+        );
+        assert_eq!(
+            pattern("a=1, b=2", "a={},"),
+            Err(Error::parse_at(1, 6, "unexpected 'b=2'").with_text("b=2"))
+        );
+    }
+}
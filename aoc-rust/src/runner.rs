@@ -0,0 +1,225 @@
+use crate::error::Error;
+use crate::input;
+use crate::solution::{DynSolution, Key, Part};
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum Status {
+    Solved,
+    Failed,
+    Missing,
+}
+
+impl Status {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Status::Solved => "solved",
+            Status::Failed => "failed",
+            Status::Missing => "missing",
+        }
+    }
+}
+
+/// The result of running one part of one day.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Outcome {
+    pub key: Key,
+    pub part: Part,
+    pub answer: Option<String>,
+    pub elapsed: Duration,
+    pub status: Status,
+    pub error: Option<Error>,
+    /// The input line a parse error points at, as rendered by
+    /// [`Error::snippet`].
+    pub snippet: Option<String>,
+}
+
+impl Outcome {
+    fn new(key: Key, part: Part, status: Status) -> Outcome {
+        Outcome {
+            key,
+            part,
+            answer: None,
+            elapsed: Duration::ZERO,
+            status,
+            error: None,
+            snippet: None,
+        }
+    }
+
+    pub fn label(&self) -> String {
+        format!("{} part {}", self.key, self.part.number())
+    }
+
+    /// Serializes the outcome as a single-line JSON object.
+    pub fn to_json(&self) -> String {
+        let opt = |s: Option<String>| s.as_deref().map_or("null".to_string(), json_string);
+
+        format!(
+            r#"{{"year":{},"day":{},"part":{},"answer":{},"elapsed_ns":{},"status":{},"error":{}}}"#,
+            self.key.year,
+            self.key.day,
+            self.part.number(),
+            opt(self.answer.clone()),
+            self.elapsed.as_nanos(),
+            json_string(self.status.as_str()),
+            opt(self.error.as_ref().map(|e| e.to_string())),
+        )
+    }
+}
+
+fn json_string(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+
+    out.push('"');
+    out
+}
+
+/// Runs `parts` of a day against the input at `path`.
+///
+/// The input is read once, but parsed again for every part so that each
+/// elapsed time covers both parsing and solving. A solved part is then
+/// exported, outside of the elapsed time.
+pub fn run_day(solution: &dyn DynSolution, key: Key, parts: &[Part], path: &Path) -> Vec<Outcome> {
+    if input::is_missing(path) {
+        return parts
+            .iter()
+            .map(|part| Outcome {
+                error: Some(Error::Io(
+                    std::io::ErrorKind::NotFound,
+                    format!("no input at {}", path.display()),
+                )),
+                ..Outcome::new(key, *part, Status::Missing)
+            })
+            .collect();
+    }
+
+    let src = input::read(path);
+
+    parts
+        .iter()
+        .map(|part| {
+            let src = match &src {
+                Ok(src) => src,
+                Err(e) => {
+                    return Outcome {
+                        error: Some(e.clone()),
+                        ..Outcome::new(key, *part, Status::Failed)
+                    }
+                }
+            };
+
+            let start = Instant::now();
+            let result = solution.parse(src).and_then(|input| {
+                let answer = solution.run(input.as_ref(), *part)?;
+                Ok((input, answer))
+            });
+            let elapsed = start.elapsed();
+            let result = result.and_then(|(input, answer)| {
+                solution.export(input.as_ref(), *part)?;
+                Ok(answer)
+            });
+
+            match result {
+                Ok(answer) => Outcome {
+                    answer: Some(answer),
+                    elapsed,
+                    ..Outcome::new(key, *part, Status::Solved)
+                },
+                Err(e) => Outcome {
+                    elapsed,
+                    snippet: e.snippet(src),
+                    error: Some(e),
+                    ..Outcome::new(key, *part, Status::Failed)
+                },
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::y2021::day06::Day06;
+
+    #[test]
+    fn outcome_to_json() {
+        let o = Outcome {
+            answer: Some("5934".to_string()),
+            elapsed: Duration::from_nanos(1200),
+            ..Outcome::new(Key::new(2021, 6), Part::One, Status::Solved)
+        };
+        assert_eq!(
+            o.to_json(),
+            r#"{"year":2021,"day":6,"part":1,"answer":"5934","elapsed_ns":1200,"status":"solved","error":null}"#
+        );
+
+        let o = Outcome {
+            error: Some(Error::InvalidState("bad \"input\"\n\u{1}".to_string())),
+            ..Outcome::new(Key::new(2021, 4), Part::Two, Status::Failed)
+        };
+        assert_eq!(
+            o.to_json(),
+            r#"{"year":2021,"day":4,"part":2,"answer":null,"elapsed_ns":0,"status":"failed","error":"invalid puzzle state: bad \"input\"\n\u0001"}"#
+        );
+    }
+
+    #[test]
+    fn run_day_missing_input() {
+        let outcomes = run_day(
+            &Day06::default(),
+            Key::new(2021, 6),
+            &[Part::One, Part::Two],
+            Path::new("inputs/0/00.txt"),
+        );
+
+        assert_eq!(outcomes.len(), 2);
+        assert_eq!(outcomes[0].status, Status::Missing);
+        assert_eq!(outcomes[0].answer, None);
+        assert_eq!(
+            outcomes[1].error.as_ref().map(|e| e.to_string()),
+            Some("no input at inputs/0/00.txt".to_string())
+        );
+    }
+
+    #[test]
+    fn run_day_from_file() {
+        let path = std::env::temp_dir().join("aoc-rust-runner-day06.txt");
+        std::fs::write(&path, "3,4,3,1,2").unwrap();
+
+        let outcomes = run_day(&Day06::default(), Key::new(2021, 6), &[Part::One], &path);
+
+        assert_eq!(outcomes.len(), 1);
+        assert_eq!(outcomes[0].status, Status::Solved);
+        assert_eq!(outcomes[0].answer.as_deref(), Some("5934"));
+        assert_eq!(outcomes[0].label(), "y2021/day06 part 1");
+    }
+
+    #[test]
+    fn run_day_parse_error() {
+        let path = std::env::temp_dir().join("aoc-rust-runner-day06-bad.txt");
+        std::fs::write(&path, "3,4,3,l,2\n").unwrap();
+
+        let outcomes = run_day(&Day06::default(), Key::new(2021, 6), &[Part::One], &path);
+
+        assert_eq!(outcomes[0].status, Status::Failed);
+        assert_eq!(
+            outcomes[0].snippet.as_deref(),
+            Some("  1 | 3,4,3,l,2\n    |       ^")
+        );
+    }
+}
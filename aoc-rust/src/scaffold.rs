@@ -0,0 +1,144 @@
+use crate::error::Error;
+use crate::input;
+use crate::solution::Key;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const TEMPLATE: &str = r##"use crate::error::Error;
+use crate::solution::Solution;
+use std::str::FromStr;
+
+#[derive(Default)]
+pub struct Day{DAY};
+
+impl Solution for Day{DAY} {
+    type Input = Puzzle;
+    type Answer1 = u64;
+    type Answer2 = u64;
+
+    fn parse(&self, src: &str) -> Result<Self::Input, Error> {
+        src.parse::<Puzzle>()
+    }
+
+    fn part1(&self, input: &Self::Input) -> Result<Self::Answer1, Error> {
+        // TODO: solve part 1
+        Ok(input.lines.len() as u64)
+    }
+
+    fn part2(&self, input: &Self::Input) -> Result<Self::Answer2, Error> {
+        // TODO: solve part 2
+        Ok(input.lines.len() as u64)
+    }
+}
+
+#[derive(PartialEq, Eq, Debug, Default)]
+pub struct Puzzle {
+    lines: Vec<String>,
+}
+
+impl FromStr for Puzzle {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let lines = s.lines().map(|s| s.to_string()).collect();
+        Ok(Puzzle { lines })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn example_case() {
+        let src = r#""#;
+        let input = Day{DAY}.parse(src).unwrap();
+
+        assert_eq!(Ok(0), Day{DAY}.part1(&input));
+    }
+
+    #[test]
+    fn example_case_part2() {
+        let src = r#""#;
+        let input = Day{DAY}.parse(src).unwrap();
+
+        assert_eq!(Ok(0), Day{DAY}.part2(&input));
+    }
+}
+"##;
+
+/// Renders the source of a new day from the template.
+pub fn render(day: u8) -> String {
+    TEMPLATE.replace("{DAY}", &format!("{:02}", day))
+}
+
+/// Creates `src_dir/yYYYY/dayNN.rs` and an empty input under `inputs_dir`,
+/// returning the created paths. Existing files are never overwritten.
+pub fn create(src_dir: &Path, inputs_dir: &Path, key: &Key) -> Result<Vec<PathBuf>, Error> {
+    let source = src_dir
+        .join(format!("y{}", key.year))
+        .join(format!("day{:02}.rs", key.day));
+    let input = inputs_dir.join(input::default_path(key).strip_prefix("inputs").unwrap());
+
+    if source.exists() {
+        return Err(Error::Io(
+            std::io::ErrorKind::AlreadyExists,
+            format!("{} already exists", source.display()),
+        ));
+    }
+
+    let mut created = vec![];
+
+    write_new(&source, &render(key.day))?;
+    created.push(source);
+
+    if !input.exists() {
+        write_new(&input, "")?;
+        created.push(input);
+    }
+
+    Ok(created)
+}
+
+fn write_new(path: &Path, contents: &str) -> Result<(), Error> {
+    let err =
+        |e: std::io::Error| Error::Io(e.kind(), format!("cannot create {}: {}", path.display(), e));
+
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(err)?;
+    }
+    fs::write(path, contents).map_err(err)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_render() {
+        let src = render(8);
+
+        assert!(src.contains("pub struct Day08;"));
+        assert!(src.contains("impl Solution for Day08 {"));
+        assert!(!src.contains("{DAY}"));
+    }
+
+    #[test]
+    fn test_create() {
+        let base = std::env::temp_dir().join("aoc-rust-scaffold");
+        let _ = fs::remove_dir_all(&base);
+        let (src, inputs) = (base.join("src"), base.join("inputs"));
+
+        let created = create(&src, &inputs, &Key::new(2021, 8)).unwrap();
+        assert_eq!(
+            created,
+            vec![src.join("y2021/day08.rs"), inputs.join("2021/08.txt")]
+        );
+        assert_eq!(fs::read_to_string(&created[0]).unwrap(), render(8));
+        assert_eq!(fs::read_to_string(&created[1]).unwrap(), "");
+
+        assert!(create(&src, &inputs, &Key::new(2021, 8)).is_err());
+
+        fs::remove_dir_all(&base).unwrap();
+    }
+}
@@ -0,0 +1,203 @@
+use crate::error::Error;
+use std::any::Any;
+use std::fmt::Display;
+use std::str::FromStr;
+
+/// A puzzle solution for a single day.
+///
+/// The input is parsed once and then shared by both parts, so that callers can
+/// capture, compare or time each step separately.
+pub trait Solution {
+    type Input: 'static;
+    type Answer1: Display;
+    type Answer2: Display;
+
+    fn parse(&self, src: &str) -> Result<Self::Input, Error>;
+    fn part1(&self, input: &Self::Input) -> Result<Self::Answer1, Error>;
+    fn part2(&self, input: &Self::Input) -> Result<Self::Answer2, Error>;
+
+    /// Sets an option given on the command line as `--opt KEY=VALUE`. By
+    /// default a solution has no options and rejects every key.
+    fn set_option(&mut self, key: &str, _value: &str) -> Result<(), Error> {
+        Err(Error::Usage(format!("unknown option '{}'", key)))
+    }
+
+    /// Writes any extra output the options ask for, once a part has been
+    /// solved. Callers run it once per part and outside of any timing, so
+    /// that repeated runs do not repeat it. By default there is none.
+    fn export(&self, _input: &Self::Input, _part: Part) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+#[derive(PartialEq, Eq, Debug, Clone, Copy, PartialOrd, Ord, Hash)]
+pub enum Part {
+    One,
+    Two,
+}
+
+impl Part {
+    pub const ALL: [Part; 2] = [Part::One, Part::Two];
+
+    pub fn number(&self) -> u8 {
+        match self {
+            Part::One => 1,
+            Part::Two => 2,
+        }
+    }
+}
+
+impl FromStr for Part {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "1" => Ok(Part::One),
+            "2" => Ok(Part::Two),
+            _ => Err(format!("invalid part '{}': must be 1 or 2", s)),
+        }
+    }
+}
+
+/// Identifies a registered solution, e.g. `y2021/day04`.
+#[derive(PartialEq, Eq, Debug, Clone, Copy, PartialOrd, Ord, Hash)]
+pub struct Key {
+    pub year: u16,
+    pub day: u8,
+}
+
+impl Key {
+    pub fn new(year: u16, day: u8) -> Key {
+        Key { year, day }
+    }
+}
+
+impl Display for Key {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "y{}/day{:02}", self.year, self.day)
+    }
+}
+
+impl FromStr for Key {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("invalid name '{}': expected the form y2021/day04", s);
+
+        let (year, day) = s.split_once('/').ok_or_else(invalid)?;
+        let year = year
+            .strip_prefix('y')
+            .and_then(|y| y.parse::<u16>().ok())
+            .ok_or_else(invalid)?;
+        let day = day
+            .strip_prefix("day")
+            .and_then(|d| d.parse::<u8>().ok())
+            .ok_or_else(invalid)?;
+
+        Ok(Key { year, day })
+    }
+}
+
+/// Type-erased form of `Solution` so that solutions with different input and
+/// answer types can be stored in one registry.
+pub trait DynSolution {
+    fn parse(&self, src: &str) -> Result<Box<dyn Any>, Error>;
+    fn run(&self, input: &dyn Any, part: Part) -> Result<String, Error>;
+    fn set_option(&mut self, key: &str, value: &str) -> Result<(), Error>;
+    fn export(&self, input: &dyn Any, part: Part) -> Result<(), Error>;
+}
+
+impl<S: Solution> DynSolution for S {
+    fn parse(&self, src: &str) -> Result<Box<dyn Any>, Error> {
+        Ok(Box::new(Solution::parse(self, src)?))
+    }
+
+    fn run(&self, input: &dyn Any, part: Part) -> Result<String, Error> {
+        let input = downcast::<S>(input);
+
+        match part {
+            Part::One => self.part1(input).map(|a| a.to_string()),
+            Part::Two => self.part2(input).map(|a| a.to_string()),
+        }
+    }
+
+    fn set_option(&mut self, key: &str, value: &str) -> Result<(), Error> {
+        Solution::set_option(self, key, value)
+    }
+
+    fn export(&self, input: &dyn Any, part: Part) -> Result<(), Error> {
+        Solution::export(self, downcast::<S>(input), part)
+    }
+}
+
+fn downcast<S: Solution>(input: &dyn Any) -> &S::Input {
+    input
+        .downcast_ref::<S::Input>()
+        .expect("input was not produced by this solution")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    struct Sum;
+
+    impl Solution for Sum {
+        type Input = Vec<u64>;
+        type Answer1 = u64;
+        type Answer2 = usize;
+
+        fn parse(&self, src: &str) -> Result<Self::Input, Error> {
+            src.split(',')
+                .map(|s| {
+                    s.trim()
+                        .parse::<u64>()
+                        .map_err(|e| Error::parse(e.to_string()))
+                })
+                .collect()
+        }
+
+        fn part1(&self, input: &Self::Input) -> Result<Self::Answer1, Error> {
+            Ok(input.iter().sum())
+        }
+
+        fn part2(&self, input: &Self::Input) -> Result<Self::Answer2, Error> {
+            if input.is_empty() {
+                return Err(Error::NoSolution("empty input".to_string()));
+            }
+            Ok(input.len())
+        }
+    }
+
+    #[test]
+    fn key_from_str() {
+        assert_eq!("y2021/day04".parse::<Key>(), Ok(Key::new(2021, 4)));
+        assert_eq!("y2021/day4".parse::<Key>(), Ok(Key::new(2021, 4)));
+        assert!("2021/day04".parse::<Key>().is_err());
+        assert!("y2021-day04".parse::<Key>().is_err());
+        assert!("y2021/dayx".parse::<Key>().is_err());
+
+        assert_eq!(Key::new(2021, 4).to_string(), "y2021/day04");
+    }
+
+    #[test]
+    fn dyn_solution() {
+        let mut s: Box<dyn DynSolution> = Box::new(Sum);
+        let input = s.parse("1,2,3").unwrap();
+
+        assert_eq!(s.run(input.as_ref(), Part::One), Ok("6".to_string()));
+        assert_eq!(s.run(input.as_ref(), Part::Two), Ok("3".to_string()));
+        assert!(s.parse("1,x").is_err());
+
+        let empty: Vec<u64> = vec![];
+        assert!(matches!(
+            s.run(&empty, Part::Two),
+            Err(Error::NoSolution(_))
+        ));
+        assert_eq!(
+            s.set_option("cost", "linear"),
+            Err(Error::Usage("unknown option 'cost'".to_string()))
+        );
+        assert_eq!(s.export(input.as_ref(), Part::One), Ok(()));
+    }
+}
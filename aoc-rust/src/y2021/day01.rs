@@ -1,30 +1,43 @@
-use std::io::BufRead;
-
-pub fn solve() {
-    println!(
-        "{}",
-        solve_inner(
-            std::io::stdin()
-                .lock()
-                .lines()
-                .flat_map(|s| s.unwrap().parse::<usize>().ok())
-        )
-    );
+use crate::error::Error;
+use crate::iter::IterExt;
+use crate::solution::Solution;
+
+#[derive(Default)]
+pub struct Day01;
+
+impl Solution for Day01 {
+    type Input = Vec<usize>;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(&self, src: &str) -> Result<Self::Input, Error> {
+        src.lines()
+            .enumerate()
+            .filter(|(_, s)| !s.trim().is_empty())
+            .map(|(i, s)| {
+                let column = s.len() - s.trim_start().len() + 1;
+                s.trim().parse::<usize>().map_err(|e| {
+                    Error::parse_at(i + 1, column, format!("invalid depth '{}': {}", s.trim(), e))
+                        .with_text(s.trim())
+                })
+            })
+            .collect()
+    }
+
+    fn part1(&self, input: &Self::Input) -> Result<Self::Answer1, Error> {
+        Ok(solve_inner(input.iter().cloned()))
+    }
+
+    fn part2(&self, input: &Self::Input) -> Result<Self::Answer2, Error> {
+        Ok(super::day01_2::solve_inner(input.iter().cloned()))
+    }
 }
 
 fn solve_inner<'a, T>(input: T) -> usize
 where
     T: Iterator<Item = usize> + 'a,
 {
-    input
-        .fold((0, None), |(cnt, prev), x| {
-            if let Some(prev) = prev {
-                (if prev < x { cnt + 1 } else { cnt }, Some(x))
-            } else {
-                (cnt, Some(x))
-            }
-        })
-        .0
+    input.pairwise().filter(|(prev, x)| prev < x).count()
 }
 
 #[cfg(test)]
@@ -49,4 +62,16 @@ mod test {
             solve_inner(src.lines().flat_map(|s| s.parse::<usize>().ok()))
         );
     }
+
+    #[test]
+    fn parse_error() {
+        assert_eq!(
+            Day01.parse("199\n\n 2x0\n"),
+            Err(Error::parse_at(
+                3,
+                2,
+                "invalid depth '2x0': invalid digit found in string"
+            ).with_text("2x0"))
+        );
+    }
 }
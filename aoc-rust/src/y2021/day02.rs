@@ -1,31 +1,55 @@
-use std::{io::BufRead, str::FromStr};
-
-pub fn solve() {
-    println!(
-        "{}",
-        solve_inner(
-            std::io::stdin()
-                .lock()
-                .lines()
-                .map(|s| s.unwrap().parse::<Command>().unwrap())
-        ),
-    );
+use crate::error::Error;
+use crate::solution::Solution;
+use std::str::FromStr;
+
+#[derive(Default)]
+pub struct Day02;
+
+impl Solution for Day02 {
+    type Input = Vec<Command>;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(&self, src: &str) -> Result<Self::Input, Error> {
+        src.lines()
+            .enumerate()
+            .map(|(i, s)| s.parse::<Command>().map_err(|e| e.offset(i, 0)))
+            .collect()
+    }
+
+    fn part1(&self, input: &Self::Input) -> Result<Self::Answer1, Error> {
+        solve_inner(input.iter().cloned())
+    }
+
+    fn part2(&self, input: &Self::Input) -> Result<Self::Answer2, Error> {
+        super::day02_2::solve_inner(input.iter().cloned())
+    }
 }
 
-fn solve_inner<I>(iter: I) -> usize
+fn solve_inner<I>(iter: I) -> Result<usize, Error>
 where
     I: Iterator<Item = Command>,
 {
-    let (depth, pos): (usize, usize) = iter.fold((0, 0), |(depth, pos), cmd| match cmd {
-        Command::Forward(x) => (depth, pos + x),
-        Command::Up(x) => (depth - x, pos),
-        Command::Down(x) => (depth + x, pos),
-    });
+    let (mut depth, mut pos) = (0, 0);
+    for cmd in iter {
+        match cmd {
+            Command::Forward(x) => pos += x,
+            Command::Up(x) => depth = rise(depth, x, "depth")?,
+            Command::Down(x) => depth += x,
+        }
+    }
+
+    Ok(depth * pos)
+}
 
-    depth * pos
+/// Subtracts `x` from `value`, which must not go below zero.
+pub(super) fn rise(value: usize, x: usize, what: &str) -> Result<usize, Error> {
+    value.checked_sub(x).ok_or_else(|| {
+        Error::InvalidState(format!("{} {} cannot go up by {}", what, value, x))
+    })
 }
 
-#[derive(PartialEq, Eq, Debug)]
+#[derive(PartialEq, Eq, Debug, Clone)]
 pub enum Command {
     Forward(usize),
     Up(usize),
@@ -42,36 +66,34 @@ impl Command {
     }
 }
 
-#[derive(PartialEq, Eq, Debug)]
-pub enum CommandParseError {
-    InvalidFormat,
-}
-
 impl FromStr for Command {
-    type Err = CommandParseError;
+    type Err = Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let mut iter = s.split(' ');
-        let mut cmd = iter
-            .next()
-            .ok_or(CommandParseError::InvalidFormat)
-            .and_then(|s| match s.to_lowercase().as_str() {
-                "forward" => Ok(Command::Forward(0)),
-                "up" => Ok(Command::Up(0)),
-                "down" => Ok(Command::Down(0)),
-                _ => Err(CommandParseError::InvalidFormat),
-            })?;
-
-        let amount = iter
+        let name = iter.next().unwrap_or_default();
+        let mut cmd = match name.to_lowercase().as_str() {
+            "forward" => Command::Forward(0),
+            "up" => Command::Up(0),
+            "down" => Command::Down(0),
+            _ => {
+                let msg = format!("unknown command '{}'", name);
+                return Err(Error::parse(msg).with_text(name));
+            }
+        };
+
+        let column = name.len() + 2;
+        let raw = iter
             .next()
-            .ok_or(CommandParseError::InvalidFormat)
-            .and_then(|s| {
-                s.parse::<usize>()
-                    .map_err(|_| CommandParseError::InvalidFormat)
-            })?;
-
-        if iter.next() != None {
-            return Err(CommandParseError::InvalidFormat);
+            .ok_or_else(|| Error::parse_at(1, column - 1, "missing amount"))?;
+        let amount = raw.parse::<usize>().map_err(|e| {
+            Error::parse_at(1, column, format!("invalid amount '{}': {}", raw, e)).with_text(raw)
+        })?;
+
+        if iter.next().is_some() {
+            let column = column + raw.len();
+            let rest = &s[column - 1..];
+            return Err(Error::parse_at(1, column, "unexpected trailing input").with_text(rest));
         }
 
         cmd.set_amount(amount);
@@ -90,19 +112,28 @@ mod test {
         assert_eq!("Down 30".parse::<Command>(), Ok(Command::Down(30)));
         assert_eq!(
             "forward 0 ".parse::<Command>(),
-            Err(CommandParseError::InvalidFormat)
+            Err(Error::parse_at(1, 10, "unexpected trailing input").with_text(" "))
         );
         assert_eq!(
             " forward 0".parse::<Command>(),
-            Err(CommandParseError::InvalidFormat)
+            Err(Error::parse_at(1, 1, "unknown command ''"))
         );
         assert_eq!(
             "forward -1".parse::<Command>(),
-            Err(CommandParseError::InvalidFormat)
+            Err(Error::parse_at(
+                1,
+                9,
+                "invalid amount '-1': invalid digit found in string"
+            )
+            .with_text("-1"))
         );
         assert_eq!(
             "hoge 0".parse::<Command>(),
-            Err(CommandParseError::InvalidFormat)
+            Err(Error::parse_at(1, 1, "unknown command 'hoge'").with_text("hoge"))
+        );
+        assert_eq!(
+            "up".parse::<Command>(),
+            Err(Error::parse_at(1, 3, "missing amount"))
         );
     }
 
@@ -115,6 +146,16 @@ up 3
 down 8
 forward 2"#;
         let ans = solve_inner(s.lines().map(|s| s.parse::<Command>().unwrap()));
-        assert_eq!(150, ans);
+        assert_eq!(Ok(150), ans);
+    }
+
+    #[test]
+    fn rise_above_surface() {
+        assert_eq!(
+            solve_inner([Command::Down(2), Command::Up(3)].into_iter()),
+            Err(Error::InvalidState(
+                "depth 2 cannot go up by 3".to_string()
+            ))
+        );
     }
 }
@@ -1,30 +1,24 @@
-pub use super::day02::{Command, CommandParseError};
-use std::io::BufRead;
+use super::day02::rise;
+pub use super::day02::Command;
+use crate::error::Error;
 
-pub fn solve() {
-    println!(
-        "{}",
-        solve_inner(
-            std::io::stdin()
-                .lock()
-                .lines()
-                .map(|s| s.unwrap().parse::<Command>().unwrap())
-        ),
-    );
-}
-
-fn solve_inner<I>(iter: I) -> usize
+pub fn solve_inner<I>(iter: I) -> Result<usize, Error>
 where
     I: Iterator<Item = Command>,
 {
-    let (_, depth, pos): (usize, usize, usize) =
-        iter.fold((0, 0, 0), |(aim, depth, pos), cmd| match cmd {
-            Command::Forward(x) => (aim, depth + aim * x, pos + x),
-            Command::Up(x) => (aim - x, depth, pos),
-            Command::Down(x) => (aim + x, depth, pos),
-        });
+    let (mut aim, mut depth, mut pos) = (0, 0, 0);
+    for cmd in iter {
+        match cmd {
+            Command::Forward(x) => {
+                depth += aim * x;
+                pos += x;
+            }
+            Command::Up(x) => aim = rise(aim, x, "aim")?,
+            Command::Down(x) => aim += x,
+        }
+    }
 
-    depth * pos
+    Ok(depth * pos)
 }
 
 #[cfg(test)]
@@ -40,6 +34,10 @@ up 3
 down 8
 forward 2"#;
         let ans = solve_inner(src.lines().map(|s| s.parse::<Command>().unwrap()));
-        assert_eq!(900, ans);
+        assert_eq!(Ok(900), ans);
+        assert!(matches!(
+            solve_inner([Command::Up(3)].into_iter()),
+            Err(Error::InvalidState(_))
+        ));
     }
 }
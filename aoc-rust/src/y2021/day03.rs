@@ -1,64 +1,38 @@
-use std::io::BufRead;
+use crate::bits::{self, Bits};
+use crate::error::Error;
+use crate::parse;
+use crate::solution::Solution;
 
-pub fn solve() {
-    let result = solve_inner(std::io::stdin().lock().lines().map(|s| s.unwrap()));
-    println!("{}", result.unwrap());
-}
-
-fn solve_inner<'a, Iter, T>(iter: Iter) -> Result<usize, String>
-where
-    Iter: Iterator<Item = T> + 'a,
-    T: AsRef<str>,
-{
-    // bit counters
-    let mut bc: Vec<i32> = Vec::new();
-    for item in make_iterator(iter) {
-        let item = match item {
-            Ok(x) => x,
-            Err(x) => return Err(x),
-        };
+#[derive(Default)]
+pub struct Day03;
 
-        if bc.len() < item.len() {
-            bc.resize(item.len(), 0);
-        }
+impl Solution for Day03 {
+    type Input = Vec<Bits>;
+    type Answer1 = u128;
+    type Answer2 = u128;
 
-        for (i, b) in item.iter().enumerate() {
-            match *b {
-                1 => bc[i] += 1,
-                0 => bc[i] -= 1,
-                _ => {}
-            }
-        }
+    fn parse(&self, src: &str) -> Result<Self::Input, Error> {
+        src.lines()
+            .filter(|s| !s.is_empty())
+            .map(|s| s.parse::<Bits>().map_err(|e| parse::within(src, s, e)))
+            .collect()
     }
 
-    let mut gamma = 0_usize;
-    let mut epsilon = 0_usize;
-
-    for (i, cnt) in bc.iter().rev().enumerate() {
-        let (dg, de) = if cnt.is_positive() { (1, 0) } else { (0, 1) };
-
-        gamma += dg << i;
-        epsilon += de << i;
+    fn part1(&self, input: &Self::Input) -> Result<Self::Answer1, Error> {
+        Ok(power_consumption(input))
     }
 
-    Ok(gamma * epsilon)
+    fn part2(&self, input: &Self::Input) -> Result<Self::Answer2, Error> {
+        super::day03_2::solve_bits(input)
+    }
 }
 
-fn make_iterator<'a, T, I>(iter: I) -> impl Iterator<Item = Result<Vec<u8>, String>> + 'a
-where
-    I: Iterator<Item = T> + 'a,
-    T: AsRef<str>,
-{
-    iter.map(|s| {
-        s.as_ref()
-            .chars()
-            .map(|c| match c {
-                '1' => Ok(1_u8),
-                '0' => Ok(0_u8),
-                _ => Err("invalid character".to_string()),
-            })
-            .collect()
-    })
+/// Gamma takes the most common bits, a tie giving `0`, and epsilon the others.
+fn power_consumption(items: &[Bits]) -> u128 {
+    let gamma = bits::most_common(items, false);
+    let epsilon = gamma.complement();
+
+    u128::from(gamma) * u128::from(epsilon)
 }
 
 #[cfg(test)]
@@ -66,15 +40,11 @@ mod test {
     use super::*;
 
     #[test]
-    fn test_make_iterator() {
-        let src = "010101\n101";
-        let result: Result<Vec<_>, _> = make_iterator(src.lines()).collect();
-
-        assert_eq!(result, Ok(vec![vec![0, 1, 0, 1, 0, 1], vec![1, 0, 1],]));
-
-        let src = "012";
-        let result: Result<Vec<_>, _> = make_iterator(src.lines()).collect();
-        assert_eq!(result, Err("invalid character".to_string()));
+    fn parse_error() {
+        assert_eq!(
+            Day03.parse("010101\n012"),
+            Err(Error::parse_at(2, 3, "invalid character '2'").with_text("2"))
+        );
     }
 
     #[test]
@@ -92,7 +62,15 @@ mod test {
 00010
 01010"#;
 
-        let ans = solve_inner(src.lines()).unwrap();
+        let items = Day03.parse(src).unwrap();
+        let ans = power_consumption(&items);
         assert_eq!(ans, 198);
     }
+
+    #[test]
+    fn tied_column() {
+        // A tie clears the gamma bit: gamma is 100 and epsilon 011.
+        let items = Day03.parse("110\n101").unwrap();
+        assert_eq!(power_consumption(&items), 12);
+    }
 }
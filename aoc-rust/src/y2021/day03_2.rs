@@ -1,34 +1,31 @@
-use std::io::BufRead;
-
-pub fn solve() {
-    let raw: Vec<_> = std::io::stdin()
-        .lock()
-        .lines()
-        .map(|s| {
-            Bits(
-                s.unwrap()
-                    .chars()
-                    .map(|c| match c {
-                        '0' => 0_u8,
-                        '1' => 1_u8,
-                        _ => panic!("unexpected char is in data"),
-                    })
-                    .collect::<Vec<u8>>(),
-            )
-        })
-        .collect();
-
-    let data = Data { raw };
-
-    let ans = solve_inner(&data);
-    println!("{}", ans);
+use crate::bits::{self, Bits};
+use crate::error::Error;
+
+pub fn solve_bits(bits: &[Bits]) -> Result<u128, Error> {
+    if bits.iter().any(|b| b.width() != bits[0].width()) {
+        return Err(Error::InvalidState(
+            "all numbers in the report must have the same width".to_string(),
+        ));
+    }
+
+    let data = Data {
+        raw: bits.to_vec(),
+    };
+
+    solve_inner(&data)
 }
 
-fn solve_inner(data: &Data) -> u64 {
-    let ogr = RatingFinder(RatingType::OxygenGenerator).find_rating(data);
-    let csr = RatingFinder(RatingType::CO2Scrubber).find_rating(data);
+fn solve_inner(data: &Data) -> Result<u128, Error> {
+    let no_rating = |name: &str| Error::NoSolution(format!("no {} rating is found", name));
+
+    let ogr = RatingFinder(RatingType::OxygenGenerator)
+        .find_rating(data)
+        .ok_or_else(|| no_rating("oxygen generator"))?;
+    let csr = RatingFinder(RatingType::CO2Scrubber)
+        .find_rating(data)
+        .ok_or_else(|| no_rating("CO2 scrubber"))?;
 
-    ogr.unwrap().to_u64() * csr.unwrap().to_u64()
+    Ok(u128::from(ogr) * u128::from(csr))
 }
 
 enum RatingType {
@@ -40,52 +37,35 @@ struct Data {
     raw: Vec<Bits>,
 }
 
-#[derive(PartialEq, Eq, Clone)]
-struct Bits(Vec<u8>);
-
-impl Bits {
-    fn nth(&self, n: usize) -> u8 {
-        self.0[n]
-    }
-
-    fn to_u64(&self) -> u64 {
-        self.0
-            .iter()
-            .rev()
-            .enumerate()
-            .fold(0, |acc, (i, x)| acc + ((*x as u64) << i))
-    }
-}
-
 struct RatingFinder(RatingType);
 
 impl RatingFinder {
     fn find_rating(&self, data: &Data) -> Option<Bits> {
-        self.find_rating_inner(&data.raw, 0)
+        let width = data.raw.first()?.width();
+        self.find_rating_inner(&data.raw, width)
     }
 
-    fn find_rating_inner(&self, data: &Vec<Bits>, pos: usize) -> Option<Bits> {
+    /// Filters `data` bit by bit from the most significant one. `pos` is the
+    /// number of bits still to consider, so the next one is bit `pos - 1`.
+    fn find_rating_inner(&self, data: &[Bits], pos: u32) -> Option<Bits> {
         match data.len() {
             0 => None,
-            1 => Some(data[0].clone()),
+            1 => Some(data[0]),
+            // the remaining numbers are all the same
+            _ if pos == 0 => Some(data[0]),
             _ => {
-                let (has1, has0): (Vec<_>, Vec<_>) =
-                    data.iter().cloned().partition(|v| v.nth(pos) > 0);
-                if has1.len() >= has0.len() {
-                    let next = match self.0 {
-                        RatingType::OxygenGenerator => has1,
-                        RatingType::CO2Scrubber => has0,
-                    };
-
-                    return self.find_rating_inner(&next, pos + 1);
-                } else {
-                    let next = match self.0 {
-                        RatingType::OxygenGenerator => has0,
-                        RatingType::CO2Scrubber => has1,
-                    };
-
-                    return self.find_rating_inner(&next, pos + 1);
-                }
+                let i = pos - 1;
+                let criteria = match self.0 {
+                    RatingType::OxygenGenerator => bits::most_common(data, true),
+                    RatingType::CO2Scrubber => bits::least_common(data, false),
+                };
+                let next: Vec<_> = data
+                    .iter()
+                    .filter(|b| b.get(i) == criteria.get(i))
+                    .cloned()
+                    .collect();
+
+                self.find_rating_inner(&next, i)
             }
         }
     }
@@ -95,6 +75,19 @@ impl RatingFinder {
 mod test {
     use super::*;
 
+    fn parse(src: &str) -> Vec<Bits> {
+        src.lines().map(|s| s.parse().unwrap()).collect()
+    }
+
+    #[test]
+    fn solve_bits_error() {
+        assert!(matches!(
+            solve_bits(&parse("01\n1")),
+            Err(Error::InvalidState(_))
+        ));
+        assert!(matches!(solve_bits(&[]), Err(Error::NoSolution(_))));
+    }
+
     #[test]
     fn case1() {
         let src = r#"00100
@@ -110,24 +103,9 @@ mod test {
 00010
 01010"#;
 
-        let raw: Vec<_> = src
-            .lines()
-            .map(|s| {
-                Bits(
-                    s.chars()
-                        .map(|c| match c {
-                            '0' => 0_u8,
-                            '1' => 1_u8,
-                            _ => panic!("unexpected char is in data"),
-                        })
-                        .collect::<Vec<u8>>(),
-                )
-            })
-            .collect();
-
-        let data = Data { raw };
+        let data = Data { raw: parse(src) };
 
         let ans = solve_inner(&data);
-        assert_eq!(ans, 230);
+        assert_eq!(ans, Ok(230));
     }
 }
@@ -1,61 +1,50 @@
-use std::io::Read;
+use crate::error::Error;
+use crate::geom::Point;
+use crate::grid::Grid;
+use crate::parse;
+use crate::solution::Solution;
 use std::str::FromStr;
 
-pub fn solve() {
-    let mut src = String::new();
-    std::io::stdin().lock().read_to_string(&mut src).unwrap();
+#[derive(Default)]
+pub struct Day04;
 
-    let mut game = src.parse::<BingoGame>().unwrap();
-    let final_score = game.play();
-    println!("{}", final_score);
-}
+impl Solution for Day04 {
+    type Input = BingoGame;
+    type Answer1 = u64;
+    type Answer2 = u64;
+
+    fn parse(&self, src: &str) -> Result<Self::Input, Error> {
+        src.parse::<BingoGame>()
+    }
 
-pub fn solve_part2() {
-    let mut src = String::new();
-    std::io::stdin().lock().read_to_string(&mut src).unwrap();
+    fn part1(&self, input: &Self::Input) -> Result<Self::Answer1, Error> {
+        input.clone().play()
+    }
 
-    let mut game = src.parse::<BingoGame>().unwrap();
-    let final_score = game.play_part2();
-    println!("{}", final_score);
+    fn part2(&self, input: &Self::Input) -> Result<Self::Answer2, Error> {
+        input.clone().play_part2()
+    }
 }
 
-#[derive(PartialEq, Eq, Debug)]
+#[derive(PartialEq, Eq, Debug, Clone)]
 struct NumberGenerator {
     nums: Vec<u8>,
 }
 
 impl FromStr for NumberGenerator {
-    type Err = String;
+    type Err = Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let nums = s
-            .split(",")
-            .filter(|s| !s.is_empty())
-            .map(|s| s.trim().parse::<u8>())
-            .collect::<Result<Vec<_>, _>>()
-            .map_err(|e| format!("NumberGenerator parse error: {}", e).to_string())?;
+        let nums = parse::list(s, ',', "number")?;
         Ok(NumberGenerator { nums })
     }
 }
 
 const SIZE: usize = 5;
 
-#[derive(PartialEq, Eq, Debug)]
-struct Pos(usize);
-
-impl Pos {
-    fn row(&self) -> usize {
-        self.0 / SIZE
-    }
-
-    fn col(&self) -> usize {
-        self.0 % SIZE
-    }
-}
-
-#[derive(PartialEq, Eq, Debug, Default)]
+#[derive(PartialEq, Eq, Debug, Default, Clone)]
 struct Board {
-    board: [u8; SIZE * SIZE],
+    board: Grid<u8>,
     rows: [u8; SIZE],
     cols: [u8; SIZE],
 }
@@ -63,11 +52,8 @@ struct Board {
 impl Board {
     fn open(&mut self, num: u8) {
         if let Some(pos) = self.find_pos(num) {
-            let row = pos.row();
-            let col = pos.col();
-
-            self.rows[row] = self.rows[row] | (1 << col);
-            self.cols[col] = self.cols[col] | (1 << row);
+            self.rows[pos.y] |= 1 << pos.x;
+            self.cols[pos.x] |= 1 << pos.y;
         }
     }
 
@@ -87,15 +73,12 @@ impl Board {
         false
     }
 
-    fn is_marked_at(&self, pos: Pos) -> bool {
-        let row = pos.row();
-        let col = pos.col();
-
-        if (self.rows[row] & (1 << col)) == 0 {
+    fn is_marked_at(&self, pos: Point<usize>) -> bool {
+        if (self.rows[pos.y] & (1 << pos.x)) == 0 {
             return false;
         }
 
-        if (self.cols[col] & (1 << row)) == 0 {
+        if (self.cols[pos.x] & (1 << pos.y)) == 0 {
             return false;
         }
 
@@ -103,87 +86,77 @@ impl Board {
     }
 
     fn all_unmarked<'a>(&'a self) -> impl Iterator<Item = u8> + 'a {
-        (0..(SIZE * SIZE)).filter_map(|x| {
-            let pos = Pos(x);
-            if self.is_marked_at(pos) {
-                None
-            } else {
-                Some(self.board[x])
-            }
-        })
+        self.board
+            .iter()
+            .filter(|(pos, _)| !self.is_marked_at(*pos))
+            .map(|(_, n)| *n)
     }
 
-    fn find_pos(&self, num: u8) -> Option<Pos> {
-        for n in 0..self.board.len() {
-            if self.board[n] == num {
-                return Some(Pos(n));
-            }
-        }
-        None
+    fn find_pos(&self, num: u8) -> Option<Point<usize>> {
+        self.board.position(|&n| n == num)
     }
 }
 
 impl FromStr for Board {
-    type Err = String;
+    type Err = Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut board = Board::default();
-        let lines: Vec<_> = s.lines().filter(|s| !s.is_empty()).collect();
+        let lines: Vec<_> = s.lines().filter(|l| !l.trim().is_empty()).collect();
 
         if lines.len() != SIZE {
             let msg = format!(
-                "Board parse error: Board size must be {0}x{0}. but the board has hight of {1}",
+                "Board size must be {0}x{0}, but the board has height of {1}",
                 SIZE,
                 lines.len()
             );
-            return Err(msg.to_string());
+            return Err(Error::parse(msg));
         }
 
+        let mut rows = vec![];
         for (row, line) in lines.iter().enumerate() {
-            let cols: Vec<_> = line.split(' ').filter(|s| !s.is_empty()).collect();
+            let nums = parse::words::<u8>(line, "number").map_err(|e| parse::within(s, line, e))?;
 
-            if cols.len() != SIZE {
+            if nums.len() != SIZE {
                 let msg = format!(
-                    "Board parse error: Board size must be {0}x{0}. but the board has width of {1} on row of {2}",
+                    "Board size must be {0}x{0}, but the board has width of {1} on row {2}",
                     SIZE,
-                    cols.len(),
-                    row,
+                    nums.len(),
+                    row + 1,
                 );
-                return Err(msg.to_string());
-            }
-
-            for (col, d) in cols.iter().enumerate() {
-                board.board[row * SIZE + col] = d
-                    .parse::<u8>()
-                    .map_err(|e| format!("Board parse error: {}", e))?;
+                return Err(parse::within(s, line, Error::parse(msg).with_text(line.trim())));
             }
+            rows.push(nums);
         }
 
-        Ok(board)
+        Ok(Board {
+            board: Grid::from_rows(rows)?,
+            ..Default::default()
+        })
     }
 }
 
-struct BingoGame {
+#[derive(Clone)]
+pub struct BingoGame {
     gen: NumberGenerator,
     boards: Vec<Board>,
 }
 
 impl BingoGame {
-    fn play(&mut self) -> u64 {
+    fn play(&mut self) -> Result<u64, Error> {
         for n in self.gen.nums.iter().cloned() {
             for b in self.boards.iter_mut() {
                 b.open(n);
 
                 if b.is_winning() {
-                    return b.all_unmarked().map(|x| x as u64).sum::<u64>() * (n as u64);
+                    return Ok(b.all_unmarked().map(|x| x as u64).sum::<u64>() * (n as u64));
                 }
             }
         }
 
-        panic!("all of boards had never won.");
+        Err(Error::NoSolution("all of boards had never won".to_string()))
     }
 
-    fn play_part2(&mut self) -> u64 {
+    fn play_part2(&mut self) -> Result<u64, Error> {
         let mut playings: Vec<_> = (0..self.boards.len()).collect();
         let mut winners = vec![];
         let mut remains = vec![];
@@ -203,47 +176,41 @@ impl BingoGame {
                 }
             }
 
-            if remains.len() == 0 && winners.len() == 1 {
-                return self.boards[winners[0]]
+            if remains.is_empty() && winners.len() == 1 {
+                return Ok(self.boards[winners[0]]
                     .all_unmarked()
                     .map(|x| x as u64)
                     .sum::<u64>()
-                    * (n as u64);
+                    * (n as u64));
             }
 
-            playings = remains.iter().cloned().collect();
+            playings = remains.to_vec();
         }
 
-        panic!("all of boards had never won.");
+        Err(Error::NoSolution(
+            "no single board is the last to win".to_string(),
+        ))
     }
 }
 
 impl FromStr for BingoGame {
-    type Err = String;
+    type Err = Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut boards = vec![];
-        let mut lines = s.lines();
-        let mut buf = String::new();
-
-        let gen =
-            NumberGenerator::from_str(lines.next().ok_or("The first line must be numbers.")?)?;
-
-        for line in lines {
-            if line.is_empty() {
-                if !buf.is_empty() {
-                    boards.push(buf.parse::<Board>()?);
-                }
-                buf.clear();
-            } else {
-                buf.push_str(line);
-                buf.push('\n');
-            }
-        }
-
-        if !buf.is_empty() {
-            boards.push(buf.parse::<Board>()?);
-        }
+        let blocks = parse::paragraphs(s);
+        let (first, rest) = blocks
+            .split_first()
+            .ok_or_else(|| Error::parse("The first line must be numbers."))?;
+
+        let gen = NumberGenerator::from_str(first).map_err(|e| parse::within(s, first, e))?;
+        let boards = rest
+            .iter()
+            .map(|block| {
+                block
+                    .parse::<Board>()
+                    .map_err(|e| parse::within(s, block, e))
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
 
         Ok(BingoGame { gen, boards })
     }
@@ -257,20 +224,20 @@ mod test {
     fn number_generator_from_str() {
         let s = "";
         assert_eq!(
-            NumberGenerator::from_str(&s),
+            NumberGenerator::from_str(s),
             Ok(NumberGenerator { nums: vec![] })
         );
 
         let s = " 1,2,3,4,5, 0 ,11 ";
         assert_eq!(
-            NumberGenerator::from_str(&s),
+            NumberGenerator::from_str(s),
             Ok(NumberGenerator {
                 nums: vec![1, 2, 3, 4, 5, 0, 11],
             })
         );
 
         let s = "a";
-        assert_eq!(NumberGenerator::from_str(&s).is_err(), true);
+        assert!(NumberGenerator::from_str(s).is_err());
     }
 
     #[test]
@@ -283,12 +250,18 @@ mod test {
  1 12 20 15 19
 "#;
 
-        let mut expect = Board::default();
-        expect.board = [
-            22, 13, 17, 11, 0, 8, 2, 23, 4, 24, 21, 9, 14, 16, 7, 6, 10, 3, 18, 5, 1, 12, 20, 15,
-            19,
-        ];
-        assert_eq!(Board::from_str(&s), Ok(expect),);
+        let expect = Board {
+            board: Grid::from_rows(vec![
+                vec![22, 13, 17, 11, 0],
+                vec![8, 2, 23, 4, 24],
+                vec![21, 9, 14, 16, 7],
+                vec![6, 10, 3, 18, 5],
+                vec![1, 12, 20, 15, 19],
+            ])
+            .unwrap(),
+            ..Default::default()
+        };
+        assert_eq!(Board::from_str(s), Ok(expect),);
     }
 
     #[test]
@@ -300,10 +273,10 @@ mod test {
  6 10  3 18  5
  1 12 20 15 19
 "#;
-        let board = Board::from_str(&s).unwrap();
-        assert_eq!(board.find_pos(22), Some(Pos(0)));
-        assert_eq!(board.find_pos(8), Some(Pos(5)));
-        assert_eq!(board.find_pos(19), Some(Pos(24)));
+        let board = Board::from_str(s).unwrap();
+        assert_eq!(board.find_pos(22), Some(Point::new(0, 0)));
+        assert_eq!(board.find_pos(8), Some(Point::new(0, 1)));
+        assert_eq!(board.find_pos(19), Some(Point::new(4, 4)));
         assert_eq!(board.find_pos(99), None);
     }
 
@@ -316,7 +289,7 @@ mod test {
  6 10  3 18  5
  1 12 20 15 19
 "#;
-        let mut board = Board::from_str(&s).unwrap();
+        let mut board = Board::from_str(s).unwrap();
         board.open(22);
         assert_eq!(
             (1, 1, false),
@@ -340,7 +313,45 @@ mod test {
         board.open(3);
         board.open(20);
         assert_eq!(board.cols[2], 31);
-        assert_eq!(board.is_winning(), true);
+        assert!(board.is_winning());
+    }
+
+    #[test]
+    fn bingo_game_from_str_error() {
+        let src = "7,4,x\n";
+        assert_eq!(
+            src.parse::<BingoGame>().err(),
+            Some(Error::parse_at(
+                1,
+                5,
+                "invalid number 'x': invalid digit found in string"
+            ).with_text("x"))
+        );
+
+        let src = r#"7,4
+
+22 13 17 11  0
+ 8  2 23  4 24
+21  9 14 16  7
+ 6 10  3 18  5
+ 1 12 20 15 19
+
+ 3 15  0  2 22
+ 9 18 13 17  5
+19  8  7 25 23
+20 11 1O 24  4
+14 21 16 12  6"#;
+        assert_eq!(
+            src.parse::<BingoGame>().err(),
+            Some(Error::parse_at(
+                12,
+                7,
+                "invalid number '1O': invalid digit found in string"
+            ).with_text("1O"))
+        );
+
+        let mut game = "7,4\n\n".parse::<BingoGame>().unwrap();
+        assert!(matches!(game.play(), Err(Error::NoSolution(_))));
     }
 
     #[test]
@@ -369,7 +380,7 @@ mod test {
         assert_eq!(game.boards.len(), 3);
 
         let final_score = game.play();
-        assert_eq!(final_score, 4512);
+        assert_eq!(final_score, Ok(4512));
     }
 
     #[test]
@@ -398,6 +409,6 @@ mod test {
         assert_eq!(game.boards.len(), 3);
 
         let final_score = game.play_part2();
-        assert_eq!(final_score, 1924);
+        assert_eq!(final_score, Ok(1924));
     }
 }
@@ -1,89 +1,98 @@
-use std::collections::HashMap;
-use std::io::Read;
+use crate::error::Error;
+use crate::geom::{Point, Vector};
+use crate::parse;
+use crate::solution::Solution;
+use std::collections::{HashMap, HashSet};
 use std::str::FromStr;
 
-pub fn solve() {
-    let mut src = String::new();
-    std::io::stdin().read_to_string(&mut src).unwrap();
-
-    let field = src.parse::<BasicField>().unwrap();
-    println!("{}", field.dengerous_points().count());
+/// The `engine` option chooses how overlaps are counted. The `lines` option
+/// makes both parts consider the given lines instead of their own.
+#[derive(Default)]
+pub struct Day05 {
+    engine: Engine,
+    mode: Option<Mode>,
 }
 
-pub fn solve_part2() {
-    let mut src = String::new();
-    std::io::stdin().read_to_string(&mut src).unwrap();
-
-    let field = src.parse::<DiagonalField>().unwrap();
-    println!("{}", field.dengerous_points().count());
-}
+impl Solution for Day05 {
+    type Input = Vec<Line>;
+    type Answer1 = usize;
+    type Answer2 = usize;
 
-#[derive(PartialEq, Eq, Default, Clone, Debug, Copy, Hash)]
-struct Point {
-    x: i64,
-    y: i64,
-}
+    fn parse(&self, src: &str) -> Result<Self::Input, Error> {
+        Ok(src.parse::<Field>()?.lines)
+    }
 
-impl Point {
-    fn unit(&self) -> Point {
-        let dx = self.x.cmp(&0) as i64;
-        let dy = self.y.cmp(&0) as i64;
+    fn part1(&self, input: &Self::Input) -> Result<Self::Answer1, Error> {
+        let mode = self.mode.unwrap_or(Mode::Axis);
+        Ok(Field::new(input.clone(), mode).overlap_count(self.engine))
+    }
 
-        Point { x: dx, y: dy }
+    fn part2(&self, input: &Self::Input) -> Result<Self::Answer2, Error> {
+        let mode = self.mode.unwrap_or(Mode::Diagonal);
+        Ok(Field::new(input.clone(), mode).overlap_count(self.engine))
     }
-}
 
-impl std::ops::Add for Point {
-    type Output = Point;
-    fn add(self, rhs: Self) -> Self::Output {
-        Point {
-            x: self.x + rhs.x,
-            y: self.y + rhs.y,
+    fn set_option(&mut self, key: &str, value: &str) -> Result<(), Error> {
+        match key {
+            "engine" => self.engine = value.parse()?,
+            "lines" => self.mode = Some(value.parse()?),
+            _ => return Err(Error::Usage(format!("unknown option '{}'", key))),
         }
+        Ok(())
     }
 }
 
-impl std::ops::Sub for Point {
-    type Output = Point;
-    fn sub(self, rhs: Self) -> Self::Output {
-        Point {
-            x: self.x - rhs.x,
-            y: self.y - rhs.y,
+/// How to count the points where lines overlap.
+#[derive(PartialEq, Eq, Debug, Default, Clone, Copy)]
+pub enum Engine {
+    /// Counts every point of every line in a map.
+    #[default]
+    Histogram,
+    /// Intersects the lines with each other, so memory grows with the
+    /// overlaps rather than the length of the lines.
+    Sweep,
+}
+
+impl FromStr for Engine {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "histogram" => Ok(Engine::Histogram),
+            "sweep" => Ok(Engine::Sweep),
+            _ => Err(Error::Usage(format!(
+                "invalid engine '{}': expected histogram or sweep",
+                s
+            ))),
         }
     }
 }
 
-impl std::ops::AddAssign for Point {
-    fn add_assign(&mut self, rhs: Self) {
-        self.x += rhs.x;
-        self.y += rhs.y;
-    }
+/// Which lines are considered; the others are ignored.
+#[derive(PartialEq, Eq, Debug, Default, Clone, Copy)]
+pub enum Mode {
+    /// Only horizontal and vertical lines.
+    #[default]
+    Axis,
+    /// Also lines at exactly 45°.
+    Diagonal,
+    /// Lines at any angle, covering the lattice points they pass through.
+    Any,
 }
 
-impl FromStr for Point {
-    type Err = String;
+impl FromStr for Mode {
+    type Err = Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut iter = s.splitn(2, ',');
-        let x = iter
-            .next()
-            .ok_or(format!("Point Parse Error: invalid format {}", s).to_string())
-            .and_then(|s| {
-                s.trim().parse::<i64>().map_err(|e| {
-                    format!("Point Parse Error: cannot parse x. {}, {}", s, e).to_string()
-                })
-            })?;
-
-        let y = iter
-            .next()
-            .ok_or(format!("Point Parse Error: invalid format {}", s).to_string())
-            .and_then(|s| {
-                s.trim().parse::<i64>().map_err(|e| {
-                    format!("Point Parse Error: cannot parse y. {}, {}", s, e).to_string()
-                })
-            })?;
-
-        Ok(Point { x, y })
+        match s {
+            "axis" => Ok(Mode::Axis),
+            "diagonal" => Ok(Mode::Diagonal),
+            "any" => Ok(Mode::Any),
+            _ => Err(Error::Usage(format!(
+                "invalid lines '{}': expected axis, diagonal or any",
+                s
+            ))),
+        }
     }
 }
 
@@ -109,13 +118,13 @@ impl Iterator for LineIterator {
 }
 
 #[derive(PartialEq, Eq, Default, Clone, Debug)]
-struct Line {
+pub struct Line {
     start: Point,
     end: Point,
 }
 
 impl Line {
-    #[allow(dead_code)]
+    #[cfg(test)]
     fn new(x1: i64, y1: i64, x2: i64, y2: i64) -> Line {
         Line {
             start: Point { x: x1, y: y1 },
@@ -123,77 +132,152 @@ impl Line {
         }
     }
 
-    #[allow(dead_code)]
+    #[cfg(test)]
     fn iter(&self) -> LineIterator {
-        self.iter_with_options(false)
+        self.iter_with_options(Mode::Axis)
     }
 
-    fn iter_with_options(&self, considering_diagonals: bool) -> LineIterator {
-        let current = self.start;
+    fn iter_with_options(&self, mode: Mode) -> LineIterator {
+        match self.segment(mode) {
+            Some(seg) => LineIterator {
+                current: seg.start,
+                delta: seg.step,
+                len: seg.len,
+            },
+            None => LineIterator {
+                current: self.start,
+                delta: Vector::default(),
+                len: -1,
+            },
+        }
+    }
+
+    /// The line as a segment, or `None` for a single point or a line `mode`
+    /// does not consider.
+    fn segment(&self, mode: Mode) -> Option<Segment> {
         let delta = self.end - self.start;
-        let len = match (delta.x == 0, delta.y == 0) {
-            (true, true) => -1,
-            (true, false) => delta.y.abs(),
-            (false, true) => delta.x.abs(),
-            (false, false) => {
-                if considering_diagonals && delta.y.abs() == delta.x.abs() {
-                    delta.y.abs()
-                } else {
-                    -1
-                }
-            }
+        let considered = match mode {
+            Mode::Axis => delta.x == 0 || delta.y == 0,
+            Mode::Diagonal => delta.x == 0 || delta.y == 0 || delta.x.abs() == delta.y.abs(),
+            Mode::Any => true,
         };
+        if delta == Vector::default() || !considered {
+            return None;
+        }
 
-        LineIterator {
-            current,
-            delta: delta.unit(),
+        // Dividing by the gcd gives the smallest step between lattice points.
+        let len = gcd(delta.x.abs(), delta.y.abs());
+        Some(Segment {
+            start: self.start,
+            step: Vector::new(delta.x / len, delta.y / len),
             len,
+        })
+    }
+}
+
+/// The points `start + step * t` for `t` in `0..=len`. `step` has coprime
+/// components, so these are all the lattice points of the segment.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+struct Segment {
+    start: Point,
+    step: Vector,
+    len: i64,
+}
+
+impl Segment {
+    fn end(&self) -> Point {
+        self.start + self.step * self.len
+    }
+
+    fn min_x(&self) -> i64 {
+        self.start.x.min(self.end().x)
+    }
+
+    fn max_x(&self) -> i64 {
+        self.start.x.max(self.end().x)
+    }
+
+    fn point(&self, t: i64) -> Point {
+        self.start + self.step * t
+    }
+
+    /// The lattice points both segments share.
+    fn intersection(&self, other: &Segment) -> Vec<Point> {
+        let w = other.start - self.start;
+        let denom = self.step.cross(&other.step);
+
+        if denom == 0 {
+            if w.cross(&self.step) != 0 {
+                return vec![];
+            }
+
+            // Collinear: find where `other` lies in terms of `t`.
+            let norm = self.step.dot(&self.step);
+            let from = w.dot(&self.step) / norm;
+            let to = from + other.step.dot(&self.step) / norm * other.len;
+            let (lo, hi) = (from.min(to).max(0), from.max(to).min(self.len));
+
+            return (lo..=hi).map(|t| self.point(t)).collect();
+        }
+
+        let (t, s) = (w.cross(&other.step), w.cross(&self.step));
+        if t % denom != 0 || s % denom != 0 {
+            return vec![];
+        }
+
+        let (t, s) = (t / denom, s / denom);
+        if (0..=self.len).contains(&t) && (0..=other.len).contains(&s) {
+            vec![self.point(t)]
+        } else {
+            vec![]
         }
     }
 }
 
+fn gcd(a: i64, b: i64) -> i64 {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
 impl FromStr for Line {
-    type Err = String;
+    type Err = Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut iter = s.splitn(2, "->");
-        let start = iter
-            .next()
-            .ok_or("Line Parse Error: invalid format".to_string())
-            .and_then(|s| s.parse::<Point>())?;
+        let fields = parse::pattern(s, "{}->{}")?;
+        let point = |f: &str| f.parse::<Point>().map_err(|e| parse::within(s, f, e));
 
-        let end = iter
-            .next()
-            .ok_or("Line Parse Error: invalid format".to_string())
-            .and_then(|s| s.parse::<Point>())?;
-
-        Ok(Line { start, end })
+        Ok(Line {
+            start: point(fields[0])?,
+            end: point(fields[1])?,
+        })
     }
 }
 
-type BasicField = Field<false>;
-type DiagonalField = Field<true>;
-
 #[derive(PartialEq, Eq, Default, Clone, Debug)]
-struct Field<const C: bool> {
+struct Field {
     lines: Vec<Line>,
-    considering_diagonals: bool,
+    mode: Mode,
 }
 
-impl<const C: bool> Field<C> {
-    #[allow(dead_code)]
-    fn new(lines: Vec<Line>) -> Field<C> {
-        Field {
-            lines,
-            considering_diagonals: C,
-        }
+impl Field {
+    fn new(lines: Vec<Line>, mode: Mode) -> Field {
+        Field { lines, mode }
     }
+
+    #[cfg(test)]
+    fn with_mode(self, mode: Mode) -> Field {
+        Field { mode, ..self }
+    }
+
     fn histogram(&self) -> HashMap<Point, usize> {
         let mut m = HashMap::new();
 
         self.lines
             .iter()
-            .flat_map(|line| line.iter_with_options(self.considering_diagonals))
+            .flat_map(|line| line.iter_with_options(self.mode))
             .for_each(|p| {
                 let ent = m.entry(p).or_default();
                 *ent += 1;
@@ -210,21 +294,50 @@ impl<const C: bool> Field<C> {
                 .map(|(p, _)| p),
         )
     }
+
+    /// The points where at least two lines overlap, found by intersecting
+    /// lines instead of rasterizing them. Sweeping over x, each line is only
+    /// paired with the earlier lines whose x range reaches it.
+    fn intersections(&self) -> HashSet<Point> {
+        let mut segments: Vec<Segment> = self
+            .lines
+            .iter()
+            .filter_map(|line| line.segment(self.mode))
+            .collect();
+        segments.sort_by_key(|s| s.min_x());
+
+        let mut active: Vec<Segment> = vec![];
+        let mut points = HashSet::new();
+        for seg in segments {
+            active.retain(|a| a.max_x() >= seg.min_x());
+            for a in active.iter() {
+                points.extend(a.intersection(&seg));
+            }
+            active.push(seg);
+        }
+        points
+    }
+
+    fn overlap_count(&self, engine: Engine) -> usize {
+        match engine {
+            Engine::Histogram => self.dengerous_points().count(),
+            Engine::Sweep => self.intersections().len(),
+        }
+    }
 }
 
-impl<const C: bool> FromStr for Field<C> {
-    type Err = String;
+/// Parses the lines of a field that considers only horizontal and vertical
+/// lines.
+impl FromStr for Field {
+    type Err = Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let lines = s
             .lines()
-            .map(|line| line.parse::<Line>())
-            .collect::<Result<Vec<_>, String>>()?;
+            .map(|line| line.parse::<Line>().map_err(|e| parse::within(s, line, e)))
+            .collect::<Result<Vec<_>, Error>>()?;
 
-        Ok(Field {
-            lines,
-            considering_diagonals: C,
-        })
+        Ok(Field::new(lines, Mode::Axis))
     }
 }
 
@@ -248,6 +361,27 @@ mod test {
             },
             src.parse().unwrap()
         );
+        assert_eq!("1,2->3,4".parse::<Line>(), src.parse::<Line>());
+    }
+
+    #[test]
+    fn line_from_str_error() {
+        assert_eq!(
+            "1,2 -> 3".parse::<Line>(),
+            Err(Error::parse_at(1, 8, "invalid point '3'").with_text("3"))
+        );
+        assert_eq!(
+            "1,2 -> 3,x".parse::<Line>(),
+            Err(Error::parse_at(
+                1,
+                10,
+                "cannot parse y 'x': invalid digit found in string"
+            ).with_text("x"))
+        );
+        assert_eq!(
+            "0,9 -> 5,9\n8,0 > 0,8".parse::<Field>(),
+            Err(Error::parse_at(2, 1, "missing '->'").with_text("8,0 > 0,8"))
+        );
     }
 
     #[test]
@@ -276,21 +410,21 @@ mod test {
 
         assert_eq!(
             Line::new(1, 1, 2, 3)
-                .iter_with_options(true)
+                .iter_with_options(Mode::Diagonal)
                 .collect::<Vec<_>>(),
             vec![],
         );
 
         assert_eq!(
             Line::new(1, 1, 2, 2)
-                .iter_with_options(true)
+                .iter_with_options(Mode::Diagonal)
                 .collect::<Vec<_>>(),
             vec![Point { x: 1, y: 1 }, Point { x: 2, y: 2 },],
         );
 
         assert_eq!(
             Line::new(3, 1, 1, 3)
-                .iter_with_options(true)
+                .iter_with_options(Mode::Diagonal)
                 .collect::<Vec<_>>(),
             vec![
                 Point { x: 3, y: 1 },
@@ -313,11 +447,12 @@ mod test {
 0,0 -> 8,8
 5,5 -> 8,2"#;
 
-        let field = src.parse::<BasicField>().unwrap();
+        let field = src.parse::<Field>().unwrap();
 
         assert_eq!(
             field,
-            BasicField::new(vec![
+            Field::new(
+                vec![
                 Line::new(0, 9, 5, 9),
                 Line::new(8, 0, 0, 8),
                 Line::new(9, 4, 3, 4),
@@ -328,7 +463,9 @@ mod test {
                 Line::new(3, 4, 1, 4),
                 Line::new(0, 0, 8, 8),
                 Line::new(5, 5, 8, 2),
-            ],),
+            ],
+                Mode::Axis
+            ),
         );
     }
 
@@ -345,7 +482,7 @@ mod test {
 0,0 -> 8,8
 5,5 -> 8,2"#;
 
-        let field = src.parse::<BasicField>().unwrap();
+        let field = src.parse::<Field>().unwrap();
 
         assert_eq!(5, field.dengerous_points().count());
     }
@@ -363,8 +500,167 @@ mod test {
 0,0 -> 8,8
 5,5 -> 8,2"#;
 
-        let field = src.parse::<DiagonalField>().unwrap();
+        let field = src.parse::<Field>().unwrap().with_mode(Mode::Diagonal);
 
         assert_eq!(12, field.dengerous_points().count());
     }
+
+    #[test]
+    fn segment_intersection() {
+        let seg = |x1, y1, x2, y2| Line::new(x1, y1, x2, y2).segment(Mode::Any).unwrap();
+        let points = |a: Segment, b: Segment| {
+            let mut v = a.intersection(&b);
+            v.sort();
+            v
+        };
+
+        assert_eq!(
+            points(seg(0, 0, 4, 0), seg(2, -2, 2, 3)),
+            vec![Point::new(2, 0)]
+        );
+        assert_eq!(points(seg(0, 0, 4, 0), seg(5, -2, 5, 3)), vec![]);
+        assert_eq!(
+            points(seg(0, 0, 4, 0), seg(6, 0, 3, 0)),
+            vec![Point::new(3, 0), Point::new(4, 0)]
+        );
+        assert_eq!(points(seg(0, 0, 4, 0), seg(0, 1, 4, 1)), vec![]);
+        assert_eq!(
+            points(seg(0, 0, 4, 4), seg(4, 4, 6, 6)),
+            vec![Point::new(4, 4)]
+        );
+        assert_eq!(
+            points(seg(0, 0, 4, 4), seg(0, 4, 4, 0)),
+            vec![Point::new(2, 2)]
+        );
+        // Diagonals that cross between lattice points do not overlap.
+        assert_eq!(points(seg(0, 0, 1, 1), seg(0, 1, 1, 0)), vec![]);
+    }
+
+    #[test]
+    fn any_angle() {
+        assert_eq!(
+            Line::new(0, 0, 6, 4)
+                .iter_with_options(Mode::Any)
+                .collect::<Vec<_>>(),
+            vec![Point::new(0, 0), Point::new(3, 2), Point::new(6, 4)],
+        );
+        assert_eq!(
+            Line::new(2, 5, 1, 2)
+                .iter_with_options(Mode::Any)
+                .collect::<Vec<_>>(),
+            vec![Point::new(2, 5), Point::new(1, 2)],
+        );
+        assert_eq!(
+            Line::new(0, 0, 6, 4)
+                .iter_with_options(Mode::Diagonal)
+                .count(),
+            0
+        );
+        assert_eq!(
+            Line::new(1, 1, 1, 1).iter_with_options(Mode::Any).count(),
+            0
+        );
+
+        let seg = |x1, y1, x2, y2| Line::new(x1, y1, x2, y2).segment(Mode::Any).unwrap();
+        assert_eq!(
+            seg(0, 0, 9, 6).intersection(&seg(12, 8, 3, 2)),
+            vec![Point::new(3, 2), Point::new(6, 4), Point::new(9, 6)]
+        );
+        assert_eq!(
+            seg(0, 0, 6, 4).intersection(&seg(0, 4, 6, 0)),
+            vec![Point::new(3, 2)]
+        );
+
+        let field = Field::new(
+            vec![
+                Line::new(0, 0, 6, 4),
+                Line::new(0, 4, 6, 0),
+                Line::new(3, 0, 3, 5),
+            ],
+            Mode::Any,
+        );
+        assert_eq!(field.overlap_count(Engine::Histogram), 1);
+        assert_eq!(field.with_mode(Mode::Diagonal).overlap_count(Engine::Histogram), 0);
+    }
+
+    #[test]
+    fn sweep_matches_histogram() {
+        let src = r#"0,9 -> 5,9
+8,0 -> 0,8
+9,4 -> 3,4
+2,2 -> 2,1
+7,0 -> 7,4
+6,4 -> 2,0
+0,9 -> 2,9
+3,4 -> 1,4
+0,0 -> 8,8
+5,5 -> 8,2"#;
+
+        assert_eq!(
+            src.parse::<Field>().unwrap().overlap_count(Engine::Sweep),
+            5
+        );
+        assert_eq!(
+            src.parse::<Field>()
+                .unwrap()
+                .with_mode(Mode::Diagonal)
+                .overlap_count(Engine::Sweep),
+            12
+        );
+
+        // A linear congruential generator, for reproducible random lines.
+        let mut state = 12345_u64;
+        let mut rand = |n: i64| {
+            state = state
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            (state >> 33) as i64 % n
+        };
+
+        for _ in 0..50 {
+            let lines: Vec<Line> = (0..40)
+                .map(|_| {
+                    let (x, y) = (rand(20), rand(20));
+                    match rand(4) {
+                        0 => Line::new(x, y, rand(20), y),
+                        1 => Line::new(x, y, x, rand(20)),
+                        2 => {
+                            let d = rand(10) - 5;
+                            Line::new(x, y, x + d, y + d)
+                        }
+                        _ => Line::new(x, y, rand(20), rand(20)),
+                    }
+                })
+                .collect();
+
+            for mode in [Mode::Axis, Mode::Diagonal, Mode::Any] {
+                let field = Field::new(lines.clone(), mode);
+                assert_eq!(
+                    field.overlap_count(Engine::Sweep),
+                    field.overlap_count(Engine::Histogram),
+                    "{:?}",
+                    mode
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn engine_option() {
+        let mut day = Day05::default();
+
+        day.set_option("engine", "sweep").unwrap();
+        assert_eq!(day.engine, Engine::Sweep);
+        assert!(matches!(
+            day.set_option("engine", "grid"),
+            Err(Error::Usage(_))
+        ));
+
+        day.set_option("lines", "any").unwrap();
+        assert_eq!(day.mode, Some(Mode::Any));
+        assert!(matches!(
+            day.set_option("lines", "curved"),
+            Err(Error::Usage(_))
+        ));
+    }
 }
@@ -1,62 +1,115 @@
+use crate::bigint::BigUint;
+use crate::error::Error;
+use crate::matrix::{DynMod, Matrix, Scalar};
+#[cfg(test)]
+use crate::memo::Memo;
+use crate::parse;
+use crate::solution::Solution;
 use std::collections::HashMap;
-use std::io::Read;
 use std::str::FromStr;
 
-pub fn solve() {
-    let mut src = String::new();
-    std::io::stdin().read_to_string(&mut src).unwrap();
-
-    let simulator = src.parse::<Simulator>().unwrap();
-    println!("{}", simulator.fish_num(80));
+/// The `days` option replaces the 80 and 256 days of both parts. The `modulus`
+/// option gives the populations modulo a number, which keeps huge day counts
+/// such as 10^15 fast.
+#[derive(Default)]
+pub struct Day06 {
+    days: Option<u64>,
+    modulus: Option<u64>,
 }
 
-pub fn solve_part2() {
-    let mut src = String::new();
-    std::io::stdin().read_to_string(&mut src).unwrap();
+/// Beyond this, exact populations have thousands of digits and need a modulus.
+const MAX_EXACT_DAYS: u64 = 100_000;
 
-    let simulator = src.parse::<Simulator>().unwrap();
-    println!("{}", simulator.fish_num(256));
-}
+impl Day06 {
+    fn count(&self, input: &Simulator, days: u64) -> Result<BigUint, Error> {
+        let days = self.days.unwrap_or(days);
 
-#[allow(unused)]
-fn the_number_of_fish(timer: u64, days: u64) -> u64 {
-    let mut memo = HashMap::new();
-    the_number_of_fish_memo(timer, days, &mut memo)
+        match self.modulus {
+            Some(m) => Ok(BigUint::from(input.population_mod(days, m))),
+            None if days > MAX_EXACT_DAYS => Err(Error::InvalidState(format!(
+                "more than {} days need the modulus option",
+                MAX_EXACT_DAYS
+            ))),
+            None => Ok(input.population(days)),
+        }
+    }
 }
 
-fn the_number_of_fish_memo(timer: u64, days: u64, memo: &mut HashMap<(u64, u64), u64>) -> u64 {
-    if let Some(ans) = memo.get(&(timer, days)) {
-        return *ans;
+impl Solution for Day06 {
+    type Input = Simulator;
+    type Answer1 = BigUint;
+    type Answer2 = BigUint;
+
+    fn parse(&self, src: &str) -> Result<Self::Input, Error> {
+        src.parse::<Simulator>()
+    }
+
+    fn part1(&self, input: &Self::Input) -> Result<Self::Answer1, Error> {
+        self.count(input, 80)
     }
 
-    if days == 0 {
-        let ans = 1;
-        memo.insert((timer, days), ans);
-        return ans;
+    fn part2(&self, input: &Self::Input) -> Result<Self::Answer2, Error> {
+        self.count(input, 256)
     }
 
-    if timer == 0 {
-        let ans1 = the_number_of_fish_memo(6, days - 1, memo);
-        memo.insert((timer, days), ans1);
+    fn set_option(&mut self, key: &str, value: &str) -> Result<(), Error> {
+        let number = value
+            .parse::<u64>()
+            .map_err(|e| Error::Usage(format!("invalid {} '{}': {}", key, value, e)));
 
-        let ans2 = the_number_of_fish_memo(8, days - 1, memo);
-        memo.insert((timer, days), ans2);
-        return ans1 + ans2;
+        match key {
+            "days" => self.days = Some(number?),
+            "modulus" => match number? {
+                0 => return Err(Error::Usage("modulus must be positive".to_string())),
+                m => self.modulus = Some(m),
+            },
+            _ => return Err(Error::Usage(format!("unknown option '{}'", key))),
+        }
+        Ok(())
     }
+}
+
+#[cfg(test)]
+fn the_number_of_fish(timer: u64, days: u64) -> u64 {
+    the_number_of_fish_memo(timer, days, &mut Memo::new())
+}
+
+#[cfg(test)]
+fn the_number_of_fish_memo(timer: u64, days: u64, memo: &mut Memo<(u64, u64), u64>) -> u64 {
+    memo.get_or_compute((timer, days), |memo| match (timer, days) {
+        (_, 0) => 1,
+        (0, _) => {
+            the_number_of_fish_memo(6, days - 1, memo) + the_number_of_fish_memo(8, days - 1, memo)
+        }
+        _ => the_number_of_fish_memo(timer - 1, days - 1, memo),
+    })
+}
+
+const TIMERS: usize = 9;
 
-    let ans = the_number_of_fish_memo(timer - 1, days - 1, memo);
-    memo.insert((timer, days), ans);
-    return ans;
+/// Maps the number of fish per timer value on one day to the next: timers
+/// count down, and each fish at 0 resets to 6 and spawns a new one at 8.
+/// `one` is the entry for a move, normally `T::one()`.
+fn transition<T: Scalar>(one: &T) -> Matrix<T> {
+    let mut m = Matrix::zeros(TIMERS, TIMERS);
+    for t in 0..TIMERS - 1 {
+        m[(t, t + 1)] = one.clone();
+    }
+    m[(6, 0)] = one.clone();
+    m[(8, 0)] = one.clone();
+    m
 }
 
 #[derive(PartialEq, Eq, Debug, Default)]
-struct Simulator {
+pub struct Simulator {
     fish: Vec<u64>,
 }
 
 impl Simulator {
+    /// Counts the fish after `days` days by memoized recursion per fish.
+    #[cfg(test)]
     fn fish_num(&self, days: u64) -> u64 {
-        let mut memo = HashMap::new();
+        let mut memo = Memo::new();
         let mut total = 0;
 
         for f in self.fish.iter() {
@@ -65,16 +118,58 @@ impl Simulator {
 
         total
     }
+
+    /// Counts the fish after `days` days in O(log days) time, by raising the
+    /// daily transition of the timer counts to the power `days`. Pick `T` to
+    /// fit the answer, e.g. `u128` or `Mod<P>` for huge day counts.
+    pub fn population<T: Scalar>(&self, days: u64) -> T {
+        self.population_with(days, T::one())
+    }
+
+    /// Like `population`, but modulo `modulus`, which is chosen at run time.
+    pub fn population_mod(&self, days: u64, modulus: u64) -> u64 {
+        self.population_with(days, DynMod::new(1, modulus)).value()
+    }
+
+    /// Counts with `one` as the unit, so that it carries the modulus of a
+    /// `DynMod` into the computation.
+    fn population_with<T: Scalar>(&self, days: u64, one: T) -> T {
+        // A fish with a timer above 8 only counts down until it reaches 8, so
+        // it joins the transition that many days later.
+        let max = TIMERS as u64 - 1;
+        let mut delayed: HashMap<u64, Vec<T>> = HashMap::new();
+        let mut total = T::zero();
+
+        for &f in self.fish.iter() {
+            let delay = f.saturating_sub(max);
+            if delay > days {
+                total = total + one.clone();
+                continue;
+            }
+
+            let counts = delayed
+                .entry(delay)
+                .or_insert_with(|| vec![T::zero(); TIMERS]);
+            let t = (f - delay) as usize;
+            counts[t] = counts[t].clone() + one.clone();
+        }
+
+        for (delay, counts) in delayed {
+            total = transition(&one)
+                .pow(days - delay)
+                .apply(&counts)
+                .into_iter()
+                .fold(total, |acc, n| acc + n);
+        }
+        total
+    }
 }
 
 impl FromStr for Simulator {
-    type Err = String;
+    type Err = Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let fish = s
-            .split(',')
-            .map(|s| s.trim().parse::<u64>().map_err(|e| format!("{}", e).to_string()))
-            .collect::<Result<_, _>>()?;
+        let fish = parse::list(s, ',', "timer")?;
         Ok(Simulator { fish })
     }
 }
@@ -82,6 +177,7 @@ impl FromStr for Simulator {
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::matrix::Mod;
 
     #[test]
     fn simulator_from_str() {
@@ -96,6 +192,18 @@ mod test {
         );
     }
 
+    #[test]
+    fn simulator_from_str_error() {
+        assert_eq!(
+            "3,4, a,1".parse::<Simulator>(),
+            Err(Error::parse_at(
+                1,
+                6,
+                "invalid timer 'a': invalid digit found in string"
+            ).with_text("a"))
+        );
+    }
+
     #[test]
     fn test_the_number_of_fish() {
         /*
@@ -151,4 +259,69 @@ mod test {
 
         assert_eq!(26984457539, simulator.fish_num(256));
     }
+
+    #[test]
+    fn population() {
+        let simulator = "3,4,3,1,2".parse::<Simulator>().unwrap();
+
+        for days in [0, 1, 18, 80, 256] {
+            assert_eq!(simulator.population::<u64>(days), simulator.fish_num(days));
+        }
+
+        type P = Mod<1_000_000_007>;
+        let big = simulator.population::<u128>(500);
+        assert_eq!(
+            simulator.population::<P>(500),
+            P::new((big % 1_000_000_007) as u64)
+        );
+        assert_eq!(
+            simulator.population_mod(1_000_000_000_000_000, 1_000_000_007),
+            simulator.population::<P>(1_000_000_000_000_000).value()
+        );
+
+        let exact = simulator.population::<BigUint>(2000).to_string();
+        let rem = exact
+            .bytes()
+            .fold(0, |acc, d| (acc * 10 + (d - b'0') as u64) % 1_000_000_007);
+        assert_eq!(simulator.population::<P>(2000), P::new(rem));
+        assert!(exact.len() > u128::MAX.to_string().len());
+
+        let simulator = Simulator {
+            fish: vec![9, 3, 20, 12, 0],
+        };
+        for days in [0, 1, 5, 11, 12, 13, 80, 256] {
+            assert_eq!(simulator.population::<u64>(days), simulator.fish_num(days));
+        }
+        assert_eq!(
+            Day06::default().part1(&"9".parse().unwrap()),
+            Ok(BigUint::from(642_u64))
+        );
+    }
+
+    #[test]
+    fn day_and_modulus_options() {
+        let input = "3,4,3,1,2".parse::<Simulator>().unwrap();
+        let mut day = Day06::default();
+
+        day.set_option("days", "18").unwrap();
+        assert_eq!(day.part1(&input), Ok(BigUint::from(26_u64)));
+        assert_eq!(day.part2(&input), Ok(BigUint::from(26_u64)));
+
+        day.set_option("modulus", "7").unwrap();
+        assert_eq!(day.part1(&input), Ok(BigUint::from(26 % 7_u64)));
+
+        day.set_option("days", "1000000000000000").unwrap();
+        assert_eq!(
+            day.part1(&input),
+            Ok(BigUint::from(input.population::<Mod<7>>(1_000_000_000_000_000).value()))
+        );
+
+        let mut day = Day06::default();
+        day.set_option("days", "1000000000000000").unwrap();
+        assert!(matches!(day.part1(&input), Err(Error::InvalidState(_))));
+
+        assert!(matches!(day.set_option("modulus", "0"), Err(Error::Usage(_))));
+        assert!(matches!(day.set_option("days", "-1"), Err(Error::Usage(_))));
+        assert!(matches!(day.set_option("speed", "1"), Err(Error::Usage(_))));
+    }
 }
@@ -1,79 +1,455 @@
-use std::io::Read;
+use crate::error::Error;
+use crate::parse;
+use crate::solution::{Part, Solution};
+use std::cmp::Ordering;
+use std::fmt::Write;
+use std::fs;
+use std::ops::RangeInclusive;
+use std::path::{Path, PathBuf};
 
-pub fn solve() {
-    let mut src = String::new();
-    std::io::stdin().read_to_string(&mut src).unwrap();
+/// Both parts use the cost set with the `cost` option instead of their own,
+/// if given. With the `curve` option, each solved part also exports its cost
+/// curve as CSV, to the given path with `_1` or `_2` added to the file name.
+/// The `optimal` column marks the positions with the lowest cost.
+#[derive(Default)]
+pub struct Day07 {
+    cost: Option<Box<dyn CostFunction>>,
+    curve: Option<PathBuf>,
+}
+
+impl Day07 {
+    fn calculator(&self, part: Part) -> CostCalculator<'_> {
+        let default: &'static dyn CostFunction = match part {
+            Part::One => &Linear,
+            Part::Two => &Triangular,
+        };
+        CostCalculator::new(self.cost.as_deref().unwrap_or(default))
+    }
+}
+
+impl Solution for Day07 {
+    type Input = Vec<u64>;
+    type Answer1 = u64;
+    type Answer2 = u64;
+
+    fn parse(&self, src: &str) -> Result<Self::Input, Error> {
+        parse::list(src, ',', "position")
+    }
+
+    fn part1(&self, input: &Self::Input) -> Result<Self::Answer1, Error> {
+        self.calculator(Part::One).optimal_cost(input)
+    }
+
+    fn part2(&self, input: &Self::Input) -> Result<Self::Answer2, Error> {
+        self.calculator(Part::Two).optimal_cost(input)
+    }
 
-    let data: Vec<_> = src
-        .split(",")
-        .map(|s| s.trim().parse::<u64>().unwrap())
-        .collect();
+    fn set_option(&mut self, key: &str, value: &str) -> Result<(), Error> {
+        match key {
+            "cost" => self.cost = Some(parse_cost(value)?),
+            "curve" => self.curve = Some(PathBuf::from(value)),
+            _ => return Err(Error::Usage(format!("unknown option '{}'", key))),
+        }
+        Ok(())
+    }
 
-    let calc = CostCalculator::new_with_liner_cost();
-    println!("{}", calc.optimal_cost(&data));
+    fn export(&self, input: &Self::Input, part: Part) -> Result<(), Error> {
+        let path = match &self.curve {
+            Some(path) => part_path(path, part),
+            None => return Ok(()),
+        };
+        let calc = self.calculator(part);
+        let csv = curve_csv(&calc.curve(input)?, &calc.optimal(input)?.positions);
+
+        fs::write(&path, csv).map_err(|e| {
+            Error::Io(e.kind(), format!("cannot write {}: {}", path.display(), e))
+        })
+    }
 }
 
-pub fn solve_part2() {
-    let mut src = String::new();
-    std::io::stdin().read_to_string(&mut src).unwrap();
+/// Adds the part number to the file name, e.g. `curve_2.csv` for `curve.csv`.
+fn part_path(path: &Path, part: Part) -> PathBuf {
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    let name = match path.extension() {
+        Some(ext) => format!("{}_{}.{}", stem, part.number(), ext.to_string_lossy()),
+        None => format!("{}_{}", stem, part.number()),
+    };
+    path.with_file_name(name)
+}
 
-    let data: Vec<_> = src
-        .split(",")
-        .map(|s| s.trim().parse::<u64>().unwrap())
-        .collect();
+/// Formats `(position, cost)` pairs as CSV with a header row, marking the
+/// positions in the `optimal` runs with `1`.
+pub fn curve_csv(curve: &[(u64, u64)], optimal: &[RangeInclusive<u64>]) -> String {
+    let mut csv = "position,cost,optimal\n".to_string();
+    for (pos, cost) in curve {
+        let best = optimal.iter().any(|run| run.contains(pos));
+        writeln!(csv, "{},{},{}", pos, cost, best as u8).unwrap();
+    }
+    csv
+}
 
-    let calc = CostCalculator::new_with_accumulative_cost();
-    println!("{}", calc.optimal_cost(&data));
+/// The leftmost and the rightmost crab.
+fn bounds(data: &[u64]) -> Result<(u64, u64), Error> {
+    match (data.iter().min(), data.iter().max()) {
+        (Some(&lo), Some(&hi)) => Ok((lo, hi)),
+        _ => Err(no_crabs()),
+    }
 }
 
-struct CostCalculator {
-    cost_func: fn(u64, u64) -> u64,
+fn no_crabs() -> Error {
+    Error::NoSolution("there are no crabs to align".to_string())
 }
 
-impl CostCalculator {
-    fn new_with_liner_cost() -> CostCalculator {
-        CostCalculator {
-            cost_func: abs_diff as fn(u64, u64) -> u64,
+/// The fuel a crab burns to move.
+pub trait CostFunction {
+    /// The fuel the `crab`-th crab of the input burns to move `distance`
+    /// positions, or `None` if it overflows.
+    fn cost(&self, crab: usize, distance: u64) -> Option<u64>;
+
+    /// The fastest strategy that still finds the minimum total cost.
+    fn strategy(&self) -> Strategy;
+}
+
+/// One unit of fuel per step.
+pub struct Linear;
+
+impl CostFunction for Linear {
+    fn cost(&self, _crab: usize, distance: u64) -> Option<u64> {
+        Some(distance)
+    }
+
+    fn strategy(&self) -> Strategy {
+        Strategy::Median
+    }
+}
+
+/// Each step costs one unit more than the previous one.
+pub struct Triangular;
+
+impl CostFunction for Triangular {
+    fn cost(&self, _crab: usize, distance: u64) -> Option<u64> {
+        Some(distance.checked_mul(distance + 1)? / 2)
+    }
+
+    fn strategy(&self) -> Strategy {
+        Strategy::Mean
+    }
+}
+
+/// The square of the distance.
+pub struct Quadratic;
+
+impl CostFunction for Quadratic {
+    fn cost(&self, _crab: usize, distance: u64) -> Option<u64> {
+        distance.checked_mul(distance)
+    }
+
+    fn strategy(&self) -> Strategy {
+        Strategy::Mean
+    }
+}
+
+/// The base cost times a weight per crab. The weights repeat if there are
+/// more crabs than weights.
+pub struct Weighted {
+    weights: Vec<u64>,
+    base: Box<dyn CostFunction>,
+}
+
+impl Weighted {
+    /// Fails if there are no weights.
+    pub fn new(weights: Vec<u64>, base: Box<dyn CostFunction>) -> Result<Weighted, Error> {
+        if weights.is_empty() {
+            return Err(Error::Usage(
+                "a weighted cost needs at least one weight".to_string(),
+            ));
+        }
+        Ok(Weighted { weights, base })
+    }
+}
+
+impl CostFunction for Weighted {
+    fn cost(&self, crab: usize, distance: u64) -> Option<u64> {
+        self.weights[crab % self.weights.len()].checked_mul(self.base.cost(crab, distance)?)
+    }
+
+    fn strategy(&self) -> Strategy {
+        match self.base.strategy() {
+            Strategy::BruteForce => Strategy::BruteForce,
+            _ => Strategy::Ternary,
         }
     }
+}
+
+/// The base cost, but never more than `max`.
+pub struct Capped {
+    max: u64,
+    base: Box<dyn CostFunction>,
+}
+
+impl Capped {
+    pub fn new(max: u64, base: Box<dyn CostFunction>) -> Capped {
+        Capped { max, base }
+    }
+}
+
+impl CostFunction for Capped {
+    /// A base cost that overflows is capped too.
+    fn cost(&self, crab: usize, distance: u64) -> Option<u64> {
+        Some(self.base.cost(crab, distance).map_or(self.max, |c| c.min(self.max)))
+    }
 
-    fn new_with_accumulative_cost() -> CostCalculator {
+    /// A capped cost is not convex, so only trying every position is safe.
+    fn strategy(&self) -> Strategy {
+        Strategy::BruteForce
+    }
+}
+
+pub const COST_SYNTAX: &str =
+    "linear, triangular, quadratic, weighted:W1,W2,..[:BASE] or capped:MAX[:BASE]";
+
+/// Parses a cost such as `quadratic`, `weighted:1,2,3` or
+/// `capped:10:triangular`. The base of a weighted or capped cost defaults to
+/// `linear`.
+pub fn parse_cost(spec: &str) -> Result<Box<dyn CostFunction>, Error> {
+    let invalid = |msg: &str| {
+        Error::Usage(format!(
+            "invalid cost '{}': {}, expected {}",
+            spec, msg, COST_SYNTAX
+        ))
+    };
+    let number = |s: &str| {
+        s.trim()
+            .parse::<u64>()
+            .map_err(|_| invalid(&format!("invalid number '{}'", s)))
+    };
+
+    let (name, args) = match spec.split_once(':') {
+        Some((name, args)) => (name, Some(args)),
+        None => (spec, None),
+    };
+    let base = |rest: Option<&str>| match rest {
+        Some(rest) => parse_cost(rest),
+        None => Ok(Box::new(Linear) as Box<dyn CostFunction>),
+    };
+
+    match (name, args) {
+        ("linear", None) => Ok(Box::new(Linear)),
+        ("triangular", None) => Ok(Box::new(Triangular)),
+        ("quadratic", None) => Ok(Box::new(Quadratic)),
+        ("weighted", Some(args)) => {
+            let (weights, rest) = split_base(args);
+            Ok(Box::new(Weighted::new(
+                weights.split(',').map(number).collect::<Result<_, _>>()?,
+                base(rest)?,
+            )?))
+        }
+        ("capped", Some(args)) => {
+            let (max, rest) = split_base(args);
+            Ok(Box::new(Capped::new(number(max)?, base(rest)?)))
+        }
+        ("weighted" | "capped", None) => Err(invalid("missing arguments")),
+        ("linear" | "triangular" | "quadratic", Some(_)) => Err(invalid("unexpected arguments")),
+        _ => Err(invalid(&format!("unknown cost '{}'", name))),
+    }
+}
+
+/// Splits `ARGS[:BASE]` into its arguments and the base cost, if any.
+fn split_base(args: &str) -> (&str, Option<&str>) {
+    match args.split_once(':') {
+        Some((args, base)) => (args, Some(base)),
+        None => (args, None),
+    }
+}
+
+/// How to find the cheapest position.
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum Strategy {
+    /// Tries every position between the leftmost and the rightmost crab.
+    BruteForce,
+    /// The median minimizes the linear cost. With an even number of crabs,
+    /// every position between the two middle ones ties, so only one of them
+    /// is tried.
+    Median,
+    /// The triangular and quadratic costs are minimized within 1 of the mean.
+    Mean,
+    /// Narrows the range by thirds, which finds the minimum of any convex
+    /// cost in O(n log m).
+    Ternary,
+}
+
+/// The cheapest way to align the crabs.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Alignment {
+    pub cost: u64,
+    /// The runs of positions between the leftmost and the rightmost crab
+    /// with the lowest cost, in ascending order. A convex cost has a single
+    /// run.
+    pub positions: Vec<RangeInclusive<u64>>,
+}
+
+pub struct CostCalculator<'a> {
+    cost: &'a dyn CostFunction,
+    strategy: Strategy,
+}
+
+impl<'a> CostCalculator<'a> {
+    /// Uses the strategy suited to `cost`.
+    pub fn new(cost: &'a dyn CostFunction) -> CostCalculator<'a> {
         CostCalculator {
-            cost_func: acc_diff as fn(u64, u64) -> u64,
+            cost,
+            strategy: cost.strategy(),
         }
     }
 
-    fn optimal_cost(&self, data: &[u64]) -> u64 {
-        let mut min = u64::MAX;
+    pub fn new_with_liner_cost() -> CostCalculator<'static> {
+        CostCalculator::new(&Linear)
+    }
+
+    pub fn new_with_accumulative_cost() -> CostCalculator<'static> {
+        CostCalculator::new(&Triangular)
+    }
+
+    /// Replaces the strategy suited to the cost function.
+    pub fn with_strategy(self, strategy: Strategy) -> CostCalculator<'a> {
+        CostCalculator { strategy, ..self }
+    }
+
+    /// The lowest total cost to align all crabs. Fails if there are none or
+    /// a cost overflows.
+    pub fn optimal_cost(&self, data: &[u64]) -> Result<u64, Error> {
+        self.minimum(data).map(|(_, cost)| cost)
+    }
+
+    /// The lowest total cost and every position that reaches it. Fails if
+    /// there are no crabs or a cost overflows.
+    pub fn optimal(&self, data: &[u64]) -> Result<Alignment, Error> {
+        let (lo, hi) = bounds(data)?;
+        let cost = |pos| self.compute_cost(data, pos);
 
-        for n in 0..data.len() {
-            min = std::cmp::min(min, self.compute_cost(data, n.try_into().unwrap()));
+        if self.strategy == Strategy::BruteForce {
+            let mut best = u64::MAX;
+            let mut positions: Vec<RangeInclusive<u64>> = vec![];
+            for p in lo..=hi {
+                let c = cost(p)?;
+                match c.cmp(&best) {
+                    Ordering::Less => {
+                        best = c;
+                        positions = vec![p..=p];
+                    }
+                    Ordering::Equal => match positions.last_mut() {
+                        Some(run) if *run.end() + 1 == p => *run = *run.start()..=p,
+                        _ => positions.push(p..=p),
+                    },
+                    Ordering::Greater => {}
+                }
+            }
+            return Ok(Alignment {
+                cost: best,
+                positions,
+            });
+        }
+
+        // The other strategies assume a convex cost, whose ties are
+        // contiguous but may extend past the minimum found, so the ends of
+        // the run are searched for on both sides of it.
+        let (pos, best) = self.minimum(data)?;
+        let mut first = lo..=pos;
+        while first.start() < first.end() {
+            let mid = first.start() + (first.end() - first.start()) / 2;
+            first = if cost(mid)? == best {
+                *first.start()..=mid
+            } else {
+                mid + 1..=*first.end()
+            };
+        }
+        let mut last = pos..=hi;
+        while last.start() < last.end() {
+            let mid = last.end() - (last.end() - last.start()) / 2;
+            last = if cost(mid)? == best {
+                mid..=*last.end()
+            } else {
+                *last.start()..=mid - 1
+            };
         }
 
-        min
+        Ok(Alignment {
+            cost: best,
+            positions: vec![*first.start()..=*last.end()],
+        })
     }
 
-    fn compute_cost(&self, data: &[u64], step: u64) -> u64 {
-        data.iter().map(|&x| (self.cost_func)(x, step)).sum()
+    /// The position and total cost of one cheapest alignment, trying only the
+    /// positions the strategy points to.
+    fn minimum(&self, data: &[u64]) -> Result<(u64, u64), Error> {
+        let (lo, hi) = bounds(data)?;
+        let cost = |pos| self.compute_cost(data, pos);
+
+        let candidates = match self.strategy {
+            Strategy::BruteForce => lo..=hi,
+            Strategy::Median => {
+                let mut sorted = data.to_vec();
+                sorted.sort_unstable();
+                let median = sorted[sorted.len() / 2];
+                median..=median
+            }
+            Strategy::Mean => {
+                let mean = (data.iter().map(|&x| x as u128).sum::<u128>() / data.len() as u128) as u64;
+                mean.saturating_sub(1).max(lo)..=(mean + 2).min(hi)
+            }
+            Strategy::Ternary => ternary_search(lo, hi, cost)?,
+        };
+
+        let mut best: Option<(u64, u64)> = None;
+        for p in candidates {
+            let c = cost(p)?;
+            if best.is_none_or(|(_, b)| c < b) {
+                best = Some((p, c));
+            }
+        }
+        best.ok_or_else(no_crabs)
+    }
+
+    /// The total cost at every position between the leftmost and the
+    /// rightmost crab. Fails if a cost overflows.
+    pub fn curve(&self, data: &[u64]) -> Result<Vec<(u64, u64)>, Error> {
+        match (data.iter().min(), data.iter().max()) {
+            (Some(&lo), Some(&hi)) => (lo..=hi)
+                .map(|p| Ok((p, self.compute_cost(data, p)?)))
+                .collect(),
+            _ => Ok(vec![]),
+        }
     }
-}
 
-fn abs_diff(a: u64, b: u64) -> u64 {
-    if a > b {
-        a - b
-    } else {
-        b - a
+    fn compute_cost(&self, data: &[u64], step: u64) -> Result<u64, Error> {
+        data.iter()
+            .enumerate()
+            .try_fold(0_u64, |total, (i, &x)| {
+                total.checked_add(self.cost.cost(i, x.abs_diff(step))?)
+            })
+            .ok_or_else(|| {
+                Error::InvalidState(format!("the cost of position {} overflows", step))
+            })
     }
 }
 
-fn acc_diff(a: u64, b: u64) -> u64 {
-    let d = abs_diff(a, b);
+/// Narrows `lo..=hi` down to at most 3 positions containing the minimum of
+/// the convex function `f`.
+fn ternary_search<F>(mut lo: u64, mut hi: u64, f: F) -> Result<RangeInclusive<u64>, Error>
+where
+    F: Fn(u64) -> Result<u64, Error>,
+{
+    while hi - lo > 2 {
+        let m1 = lo + (hi - lo) / 3;
+        let m2 = hi - (hi - lo) / 3;
 
-    if d % 2 == 0 {
-        (1 + d) * d / 2
-    } else {
-        (d * (d - 1) / 2) + d
+        match f(m1)?.cmp(&f(m2)?) {
+            Ordering::Less => hi = m2 - 1,
+            Ordering::Greater => lo = m1 + 1,
+            Ordering::Equal => (lo, hi) = (m1, m2),
+        }
     }
+    Ok(lo..=hi)
 }
 
 #[cfg(test)]
@@ -85,10 +461,30 @@ mod test {
         let data = vec![16, 1, 2, 0, 4, 2, 7, 1, 2, 14];
         let calc = CostCalculator::new_with_liner_cost();
 
-        assert_eq!(41, calc.compute_cost(&data, 1));
-        assert_eq!(37, calc.compute_cost(&data, 2));
-        assert_eq!(39, calc.compute_cost(&data, 3));
-        assert_eq!(71, calc.compute_cost(&data, 10));
+        assert_eq!(Ok(41), calc.compute_cost(&data, 1));
+        assert_eq!(Ok(37), calc.compute_cost(&data, 2));
+        assert_eq!(Ok(39), calc.compute_cost(&data, 3));
+        assert_eq!(Ok(71), calc.compute_cost(&data, 10));
+    }
+
+    #[test]
+    fn parse_error() {
+        assert_eq!(
+            Day07::default().parse("16,1,-2"),
+            Err(Error::parse_at(
+                1,
+                6,
+                "invalid position '-2': invalid digit found in string"
+            ).with_text("-2"))
+        );
+        assert!(matches!(
+            Day07::default().part1(&vec![]),
+            Err(Error::NoSolution(_))
+        ));
+        assert!(matches!(
+            Day07::default().part2(&vec![]),
+            Err(Error::NoSolution(_))
+        ));
     }
 
     #[test]
@@ -96,7 +492,7 @@ mod test {
         let data = vec![16, 1, 2, 0, 4, 2, 7, 1, 2, 14];
         let calc = CostCalculator::new_with_liner_cost();
 
-        assert_eq!(37, calc.optimal_cost(&data));
+        assert_eq!(Ok(37), calc.optimal_cost(&data));
     }
 
     #[test]
@@ -104,6 +500,263 @@ mod test {
         let data = vec![16, 1, 2, 0, 4, 2, 7, 1, 2, 14];
         let calc = CostCalculator::new_with_accumulative_cost();
 
-        assert_eq!(168, calc.optimal_cost(&data));
+        assert_eq!(Ok(168), calc.optimal_cost(&data));
+    }
+
+    #[test]
+    fn strategies_match_brute_force() {
+        let cases: Vec<Vec<u64>> = vec![
+            vec![16, 1, 2, 0, 4, 2, 7, 1, 2, 14],
+            vec![5],
+            vec![3, 3, 3],
+            vec![0, 1000],
+            vec![1, 2, 100, 101, 102, 1000, 1001],
+            (0..50).map(|i| i * i % 37 + i / 7).collect(),
+            vec![1000, 1003, 1010],
+        ];
+        let check = |new: fn() -> CostCalculator<'static>, strategies: [Strategy; 2], data: &[u64]| {
+            let expected = new()
+                .with_strategy(Strategy::BruteForce)
+                .optimal_cost(data);
+
+            for s in strategies {
+                assert_eq!(
+                    new().with_strategy(s).optimal_cost(data),
+                    expected,
+                    "{:?} on {:?}",
+                    s,
+                    data
+                );
+            }
+        };
+
+        for data in cases.iter() {
+            check(
+                CostCalculator::new_with_liner_cost,
+                [Strategy::Median, Strategy::Ternary],
+                data,
+            );
+            check(
+                CostCalculator::new_with_accumulative_cost,
+                [Strategy::Mean, Strategy::Ternary],
+                data,
+            );
+        }
+    }
+
+    #[test]
+    fn wide_median_gap() {
+        let data = [0, 1_000_000_000_000];
+
+        assert_eq!(
+            CostCalculator::new_with_liner_cost().optimal_cost(&data),
+            Ok(1_000_000_000_000)
+        );
+        assert_eq!(Day07::default().part1(&data.to_vec()), Ok(1_000_000_000_000));
+        assert_eq!(
+            CostCalculator::new_with_liner_cost()
+                .optimal(&[0, 5, 1_000_000_000_000, 1_000_000_000_005])
+                .unwrap()
+                .positions,
+            vec![5..=1_000_000_000_000]
+        );
+    }
+
+    #[test]
+    fn brute_force_covers_value_range() {
+        let calc = CostCalculator::new_with_liner_cost().with_strategy(Strategy::BruteForce);
+
+        assert_eq!(calc.optimal_cost(&[1000, 1003, 1010]), Ok(10));
+        assert!(matches!(calc.optimal_cost(&[]), Err(Error::NoSolution(_))));
+    }
+
+    #[test]
+    fn cost_functions() {
+        let cost = |spec: &str, crab, distance| parse_cost(spec).unwrap().cost(crab, distance);
+
+        assert_eq!(cost("linear", 0, 11), Some(11));
+        assert_eq!(cost("triangular", 0, 11), Some(66));
+        assert_eq!(cost("quadratic", 0, 11), Some(121));
+        assert_eq!(cost("weighted:1,3", 3, 11), Some(33));
+        assert_eq!(cost("weighted:1,3:triangular", 2, 4), Some(10));
+        assert_eq!(cost("capped:5", 0, 11), Some(5));
+        assert_eq!(cost("capped:50:weighted:2:quadratic", 0, 4), Some(32));
+        assert_eq!(cost("capped:50:weighted:2:quadratic", 0, 5), Some(50));
+        assert_eq!(cost("quadratic", 0, u64::MAX), None);
+        assert_eq!(cost("triangular", 0, u64::MAX / 2), None);
+        assert_eq!(cost("capped:7:quadratic", 0, u64::MAX), Some(7));
+
+        for spec in ["", "cubic", "linear:2", "weighted", "weighted:", "capped:x", "capped:5:"] {
+            assert!(
+                matches!(parse_cost(spec), Err(Error::Usage(_))),
+                "{:?}",
+                spec
+            );
+        }
+    }
+
+    #[test]
+    fn custom_costs_match_brute_force() {
+        let data = vec![16, 1, 2, 0, 4, 2, 7, 1, 2, 14];
+        let specs = [
+            "quadratic",
+            "weighted:1,5,2",
+            "weighted:3,1:triangular",
+            "capped:6",
+            "capped:20:triangular",
+        ];
+
+        for spec in specs {
+            let cost = parse_cost(spec).unwrap();
+            let calc = CostCalculator::new(cost.as_ref());
+            let expected = (0..=16).map(|p| calc.compute_cost(&data, p).unwrap()).min();
+
+            assert_eq!(calc.optimal_cost(&data).ok(), expected, "{}", spec);
+        }
+        assert_eq!(
+            CostCalculator::new(&Quadratic).optimal_cost(&data),
+            CostCalculator::new(&Quadratic).compute_cost(&data, 5)
+        );
+    }
+
+    #[test]
+    fn cost_overflow() {
+        let data = vec![0, 2];
+
+        assert!(matches!(
+            Weighted::new(vec![], Box::new(Linear)),
+            Err(Error::Usage(_))
+        ));
+        assert!(matches!(parse_cost("weighted:"), Err(Error::Usage(_))));
+
+        let cost = parse_cost("weighted:18446744073709551615").unwrap();
+        for strategy in [Strategy::BruteForce, Strategy::Ternary] {
+            assert!(matches!(
+                CostCalculator::new(cost.as_ref())
+                    .with_strategy(strategy)
+                    .optimal_cost(&data),
+                Err(Error::InvalidState(_))
+            ));
+        }
+        assert!(matches!(
+            CostCalculator::new(&Linear).compute_cost(&[u64::MAX, u64::MAX], 0),
+            Err(Error::InvalidState(_))
+        ));
+    }
+
+    #[test]
+    fn cost_option() {
+        let data = vec![16, 1, 2, 0, 4, 2, 7, 1, 2, 14];
+        let mut day = Day07::default();
+
+        day.set_option("cost", "quadratic").unwrap();
+        assert_eq!(day.part1(&data), Ok(291));
+        assert_eq!(day.part2(&data), Ok(291));
+        assert!(matches!(
+            day.set_option("cost", "cubic"),
+            Err(Error::Usage(_))
+        ));
+        assert!(matches!(
+            day.set_option("speed", "1"),
+            Err(Error::Usage(_))
+        ));
+    }
+
+    #[test]
+    fn curve_export() {
+        let dir = std::env::temp_dir().join("aoc-rust-day07-curve");
+        let mut day = Day07::default();
+        day.set_option("curve", dir.join("curve.csv").to_str().unwrap())
+            .unwrap();
+        fs::create_dir_all(&dir).unwrap();
+
+        assert_eq!(day.part1(&vec![1, 3]), Ok(2));
+        assert!(!dir.join("curve_1.csv").exists());
+
+        day.export(&vec![1, 3], Part::One).unwrap();
+        assert_eq!(
+            fs::read_to_string(dir.join("curve_1.csv")).unwrap(),
+            "position,cost,optimal\n1,2,1\n2,2,1\n3,2,1\n"
+        );
+        day.export(&vec![1, 3], Part::Two).unwrap();
+        assert_eq!(
+            fs::read_to_string(dir.join("curve_2.csv")).unwrap(),
+            "position,cost,optimal\n1,3,0\n2,2,1\n3,3,0\n"
+        );
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn optimal_positions() {
+        let data = vec![16, 1, 2, 0, 4, 2, 7, 1, 2, 14];
+        let alignment = |calc: CostCalculator| calc.optimal(&data).unwrap();
+
+        assert_eq!(
+            alignment(CostCalculator::new_with_liner_cost()),
+            Alignment {
+                cost: 37,
+                positions: vec![2..=2]
+            }
+        );
+        assert_eq!(
+            alignment(CostCalculator::new_with_accumulative_cost()).positions,
+            vec![5..=5]
+        );
+        assert!(matches!(
+            CostCalculator::new(&Linear).optimal(&[]),
+            Err(Error::NoSolution(_))
+        ));
+
+        for strategy in [Strategy::BruteForce, Strategy::Median, Strategy::Ternary] {
+            let calc = CostCalculator::new(&Linear).with_strategy(strategy);
+            assert_eq!(
+                calc.optimal(&[0, 10]).unwrap().positions,
+                vec![0..=10],
+                "{:?}",
+                strategy
+            );
+            assert_eq!(calc.optimal(&[1, 3, 5, 9]).unwrap().positions, vec![3..=5]);
+        }
+
+        let wide = CostCalculator::new(&Linear).with_strategy(Strategy::Ternary);
+        assert_eq!(
+            wide.optimal(&[0, 1_000_000_000_000]),
+            Ok(Alignment {
+                cost: 1_000_000_000_000,
+                positions: vec![0..=1_000_000_000_000]
+            })
+        );
+
+        let capped = Capped::new(2, Box::new(Linear));
+        assert_eq!(
+            CostCalculator::new(&capped)
+                .optimal(&[0, 5, 10])
+                .unwrap(),
+            Alignment {
+                cost: 4,
+                positions: vec![0..=0, 5..=5, 10..=10]
+            }
+        );
+    }
+
+    #[test]
+    fn cost_curve() {
+        let calc = CostCalculator::new_with_liner_cost();
+        let curve = calc.curve(&[1, 3]).unwrap();
+
+        assert_eq!(curve, vec![(1, 2), (2, 2), (3, 2)]);
+        assert_eq!(
+            curve_csv(&curve, &[2..=2]),
+            "position,cost,optimal\n1,2,0\n2,2,1\n3,2,0\n"
+        );
+        assert_eq!(calc.curve(&[]), Ok(vec![]));
+        assert_eq!(
+            part_path(Path::new("out/curve.csv"), Part::Two),
+            PathBuf::from("out/curve_2.csv")
+        );
+        assert_eq!(
+            part_path(Path::new("curve"), Part::One),
+            PathBuf::from("curve_1")
+        );
     }
 }
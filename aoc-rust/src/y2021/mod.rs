@@ -1,10 +0,0 @@
-pub mod day01;
-pub mod day01_2;
-pub mod day02;
-pub mod day02_2;
-pub mod day03;
-pub mod day03_2;
-pub mod day04;
-pub mod day05;
-pub mod day06;
-pub mod day07;
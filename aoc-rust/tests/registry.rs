@@ -0,0 +1,29 @@
+use aoc_rust::y2021::day06::Day06;
+use aoc_rust::{registry, Key, Part, Solution};
+
+#[test]
+fn registry_contains_every_day() {
+    let m = registry();
+
+    assert!(m.contains_key(&Key::new(2021, 1)));
+    assert!(m.contains_key(&Key::new(2021, 7)));
+}
+
+#[test]
+fn solve_through_registry() {
+    let m = registry();
+    let solution = &m[&Key::new(2021, 6)];
+
+    let input = solution.parse("3,4,3,1,2").unwrap();
+    assert_eq!(
+        solution.run(input.as_ref(), Part::One),
+        Ok("5934".to_string())
+    );
+}
+
+#[test]
+fn solve_directly() {
+    let day = Day06::default();
+    let input = day.parse("3,4,3,1,2").unwrap();
+    assert_eq!(day.part2(&input).unwrap().to_string(), "26984457539");
+}
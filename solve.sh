@@ -6,4 +6,4 @@ export BASE="$(cd $(dirname $0) && pwd)"
 export PATH=$BASE/bin:$PATH
 
 make all
-aoc-rust $1 <inputs/$1
+aoc-rust run "$1"